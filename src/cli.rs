@@ -0,0 +1,485 @@
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+
+use a1_stack_analyser::{
+    analyser,
+    payload::{
+        output::{path_in_base, Output},
+        payload::Payload,
+    },
+    provider::{base::BaseProvider, fs::FsProvider},
+    rules::{loader::load_rules_from_dir, registry::RuleRegistry, self_test::self_test},
+    AnalyserOptions,
+};
+
+const USAGE: &str = "Usage: a1-stack-analyser scan <path> [options]
+       a1-stack-analyser rules check [-r <dir>]
+
+Commands:
+  scan <path>            Analyse the stack of a folder
+  rules check            Validate the built-in rules and the ones of --rules, and test
+                         that each one matches its examples
+
+Options:
+  -f, --format <format>  Output format: text, json, debug (default: text)
+  -o, --output <file>    Write the result to a file instead of stdout
+  -d, --max-depth <n>    Do not look deeper than <n> folders below <path>
+  -t, --transitive       Also list dependencies of dependencies found in lockfiles
+  -r, --rules <dir>      Load extra rules from the YAML, JSON and TOML files of <dir>
+  -q, --quiet            Do not print warnings
+  -v, --verbose          Print debug information
+  -h, --help             Print this help";
+
+/// Exit code when the command line can't be understood.
+const EXIT_USAGE: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Debug,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ScanArgs {
+    pub path: String,
+    pub format: Format,
+    pub output: Option<String>,
+    pub max_depth: Option<usize>,
+    pub transitive: bool,
+    pub rules: Option<String>,
+    pub verbosity: Verbosity,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Scan(ScanArgs),
+    /// Validate the rules without scanning anything
+    CheckRules {
+        rules: Option<String>,
+    },
+    Help,
+}
+
+pub fn run(args: &[String]) -> ExitCode {
+    let command = match parse_args(args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    match command {
+        Command::Help => {
+            println!("{}", USAGE);
+            ExitCode::SUCCESS
+        }
+        Command::Scan(args) => match scan(&args) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                ExitCode::FAILURE
+            }
+        },
+        Command::CheckRules { rules } => match check_rules(rules.as_deref()) {
+            Ok(count) => {
+                println!("{} rules are valid", count);
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                ExitCode::FAILURE
+            }
+        },
+    }
+}
+
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    let mut iter = args.iter();
+    match iter.next().map(|s| s.as_str()) {
+        Some("scan") => {}
+        Some("rules") => return parse_rules_args(iter),
+        Some("help") | Some("-h") | Some("--help") => return Ok(Command::Help),
+        Some(other) => return Err(format!("unknown command: {}", other)),
+        None => return Err("missing command".to_string()),
+    }
+
+    let mut path = None;
+    let mut format = Format::Text;
+    let mut output = None;
+    let mut max_depth = None;
+    let mut transitive = false;
+    let mut rules = None;
+    let mut verbosity = Verbosity::Normal;
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-q" | "--quiet" => verbosity = Verbosity::Quiet,
+            "-v" | "--verbose" => verbosity = Verbosity::Verbose,
+            "-t" | "--transitive" => transitive = true,
+            "-f" | "--format" => {
+                format = match flag_value(&mut iter, arg)?.as_str() {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    "debug" => Format::Debug,
+                    other => return Err(format!("unknown format: {}", other)),
+                }
+            }
+            "-o" | "--output" => output = Some(flag_value(&mut iter, arg)?),
+            "-r" | "--rules" => rules = Some(flag_value(&mut iter, arg)?),
+            "-d" | "--max-depth" => {
+                let value = flag_value(&mut iter, arg)?;
+                max_depth = Some(
+                    value.parse::<usize>().map_err(|_| format!("invalid max depth: {}", value))?,
+                );
+            }
+            flag if flag.starts_with('-') => return Err(format!("unknown option: {}", flag)),
+            value => {
+                if path.is_some() {
+                    return Err(format!("unexpected argument: {}", value));
+                }
+                path = Some(value.to_string());
+            }
+        }
+    }
+
+    let Some(path) = path else {
+        return Err("missing <path> to scan".to_string());
+    };
+
+    Ok(Command::Scan(ScanArgs {
+        path,
+        format,
+        output,
+        max_depth,
+        transitive,
+        rules,
+        verbosity,
+    }))
+}
+
+fn parse_rules_args<'a>(mut iter: impl Iterator<Item = &'a String>) -> Result<Command, String> {
+    match iter.next().map(|s| s.as_str()) {
+        Some("check") => {}
+        Some("-h") | Some("--help") => return Ok(Command::Help),
+        Some(other) => return Err(format!("unknown rules command: {}", other)),
+        None => return Err("missing rules command".to_string()),
+    }
+
+    let mut rules = None;
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-r" | "--rules" => rules = Some(flag_value(&mut iter, arg)?),
+            other => return Err(format!("unexpected argument: {}", other)),
+        }
+    }
+
+    Ok(Command::CheckRules { rules })
+}
+
+fn flag_value<'a>(
+    iter: &mut impl Iterator<Item = &'a String>,
+    flag: &str,
+) -> Result<String, String> {
+    iter.next().cloned().ok_or_else(|| format!("missing value for {}", flag))
+}
+
+fn scan(args: &ScanArgs) -> Result<(), String> {
+    init_logger(args.verbosity);
+
+    let mut provider =
+        FsProvider::new(&args.path).map_err(|e| format!("cannot scan {}: {}", args.path, e))?;
+    if let Some(max_depth) = args.max_depth {
+        provider = provider.with_max_depth(max_depth);
+    }
+    let base_path = provider.base_path();
+
+    let mut registry = RuleRegistry::with_defaults()?.with_transitive(args.transitive);
+    if let Some(dir) = &args.rules {
+        let count = load_rules_from_dir(&mut registry, Path::new(dir))?;
+        log::info!("Loaded {} rules from {}", count, dir);
+    }
+
+    let result = analyser(AnalyserOptions {
+        provider,
+        registry: &registry,
+    });
+    let rendered = render(&result, args.format, &base_path)?;
+
+    match &args.output {
+        Some(output) => {
+            fs::write(output, rendered).map_err(|e| format!("cannot write {}: {}", output, e))?;
+            log::info!("Result written to {}", output);
+        }
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
+
+/// Register every rule, built-in and from `dir`, check they find their own examples,
+/// and count them.
+fn check_rules(dir: Option<&str>) -> Result<usize, String> {
+    init_logger(Verbosity::Normal);
+
+    let mut registry = RuleRegistry::with_defaults()?;
+    if let Some(dir) = dir {
+        load_rules_from_dir(&mut registry, Path::new(dir))?;
+    }
+
+    let errors: Vec<String> = registry
+        .registered_rules
+        .iter()
+        .filter_map(|rule| self_test(rule, &registry).err())
+        .flatten()
+        .map(|e| e.to_string())
+        .collect();
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    Ok(registry.registered_rules.len())
+}
+
+fn render(pl: &Payload, format: Format, base_path: &str) -> Result<String, String> {
+    match format {
+        Format::Text => {
+            let mut out = String::new();
+            render_text(pl, 0, base_path, &mut out);
+            Ok(out)
+        }
+        Format::Json => {
+            let output = Output::new(pl).relative_to(base_path);
+            let json = output.to_json().map_err(|e| format!("cannot serialize result: {}", e))?;
+            Ok(format!("{}\n", json))
+        }
+        Format::Debug => Ok(format!("{:#?}\n", pl)),
+    }
+}
+
+/// Print a Payload and its childs as an indented tree, paths relative to the scanned folder.
+fn render_text(pl: &Payload, depth: usize, base_path: &str, out: &mut String) {
+    let indent = "  ".repeat(depth);
+
+    let mut paths: Vec<String> = pl.path.iter().map(|p| path_in_base(p, base_path)).collect();
+    paths.sort();
+    let tech = pl.tech.as_ref().map(|t| format!(" [{}]", t)).unwrap_or_default();
+    out.push_str(&format!(
+        "{}- {}{} ({})\n",
+        indent,
+        pl.name,
+        tech,
+        paths.join(", ")
+    ));
+
+    if !pl.techs.is_empty() {
+        let mut techs: Vec<&String> = pl.techs.iter().collect();
+        techs.sort();
+        let techs: Vec<&str> = techs.into_iter().map(|t| t.as_str()).collect();
+        out.push_str(&format!("{}    techs: {}\n", indent, techs.join(", ")));
+    }
+
+    if !pl.languages.is_empty() {
+        let mut languages: Vec<(&String, &i32)> = pl.languages.iter().collect();
+        languages.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        let languages: Vec<String> = languages
+            .into_iter()
+            .map(|(name, count)| format!("{} ({})", name, count))
+            .collect();
+        out.push_str(&format!(
+            "{}    languages: {}\n",
+            indent,
+            languages.join(", ")
+        ));
+    }
+
+    if !pl.dependencies.is_empty() {
+        out.push_str(&format!(
+            "{}    dependencies: {}\n",
+            indent,
+            pl.dependencies.len()
+        ));
+    }
+
+    if let Some(hosting) = &pl.in_component {
+        out.push_str(&format!("{}    in component: {}\n", indent, hosting.name));
+    }
+
+    if !pl.edges.is_empty() {
+        let targets: Vec<&str> = pl.edges.iter().map(|edge| edge.target.name.as_str()).collect();
+        out.push_str(&format!("{}    edges: {}\n", indent, targets.join(", ")));
+    }
+
+    for child in &pl.childs {
+        render_text(child, depth + 1, base_path, out);
+    }
+}
+
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{}] {}", record.level(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+fn init_logger(verbosity: Verbosity) {
+    // Fails only if a logger is already installed, which is fine
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(match verbosity {
+        Verbosity::Quiet => log::LevelFilter::Error,
+        Verbosity::Normal => log::LevelFilter::Warn,
+        Verbosity::Verbose => log::LevelFilter::Debug,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args_scan() {
+        let command = parse_args(&args(&["scan", "."])).unwrap();
+        assert_eq!(
+            command,
+            Command::Scan(ScanArgs {
+                path: ".".to_string(),
+                format: Format::Text,
+                output: None,
+                max_depth: None,
+                transitive: false,
+                rules: None,
+                verbosity: Verbosity::Normal,
+            })
+        );
+
+        let command = parse_args(&args(&[
+            "scan", "-f", "debug", "--output", "out.txt", "-d", "3", "-q", "-t", "-r", "rules",
+            "./repo",
+        ]))
+        .unwrap();
+        assert_eq!(
+            command,
+            Command::Scan(ScanArgs {
+                path: "./repo".to_string(),
+                format: Format::Debug,
+                output: Some("out.txt".to_string()),
+                max_depth: Some(3),
+                transitive: true,
+                rules: Some("rules".to_string()),
+                verbosity: Verbosity::Quiet,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_args_rules_check() {
+        assert_eq!(
+            parse_args(&args(&["rules", "check"])).unwrap(),
+            Command::CheckRules { rules: None }
+        );
+        assert_eq!(
+            parse_args(&args(&["rules", "check", "--rules", "rules"])).unwrap(),
+            Command::CheckRules {
+                rules: Some("rules".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_args_help() {
+        assert_eq!(parse_args(&args(&["help"])).unwrap(), Command::Help);
+        assert_eq!(parse_args(&args(&["scan", "-h"])).unwrap(), Command::Help);
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(parse_args(&args(&[])).is_err());
+        assert!(parse_args(&args(&["analyse", "."])).is_err());
+        assert!(parse_args(&args(&["scan"])).is_err());
+        assert!(parse_args(&args(&["scan", ".", "other"])).is_err());
+        assert!(parse_args(&args(&["scan", ".", "--format", "xml"])).is_err());
+        assert!(parse_args(&args(&["scan", ".", "--max-depth", "-1"])).is_err());
+        assert!(parse_args(&args(&["scan", ".", "--output"])).is_err());
+        assert!(parse_args(&args(&["scan", ".", "--rules"])).is_err());
+        assert!(parse_args(&args(&["scan", ".", "--unknown"])).is_err());
+        assert!(parse_args(&args(&["rules"])).is_err());
+        assert!(parse_args(&args(&["rules", "list"])).is_err());
+        assert!(parse_args(&args(&["rules", "check", "."])).is_err());
+    }
+
+    #[test]
+    fn test_run_exit_codes() {
+        assert_eq!(run(&args(&["scan"])), ExitCode::from(EXIT_USAGE));
+        assert_eq!(
+            run(&args(&["scan", "-q", "/nonexistent/a1-stack-analyser"])),
+            ExitCode::FAILURE
+        );
+
+        assert_eq!(run(&args(&["rules", "check"])), ExitCode::SUCCESS);
+        let dir = std::env::temp_dir().join(format!("rules-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("a.yml"),
+            "tech: unknown\nname: Unknown\ntype: tool",
+        )
+        .unwrap();
+        let code = run(&args(&["rules", "check", "-r", &dir.to_string_lossy()]));
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(code, ExitCode::FAILURE);
+    }
+
+    #[test]
+    fn test_render_text() {
+        let mut root = Payload::new("main", "/repo");
+        root.add_lang("TOML", 1, &RuleRegistry::new());
+        let mut child = Payload::new("rust-server", "/repo/Cargo.toml");
+        child.tech = Some("rust".to_string());
+        child.dependencies.push(vec![
+            "rust".to_string(),
+            "dotenv".to_string(),
+            "0.15.0".to_string(),
+        ]);
+        root.childs.push(child);
+
+        assert_eq!(
+            render(&root, Format::Text, "/repo").unwrap(),
+            "- main (/)\n    languages: TOML (1)\n  - rust-server [rust] (/Cargo.toml)\n      dependencies: 1\n"
+        );
+    }
+
+    #[test]
+    fn test_render_json() {
+        let mut root = Payload::new("main", "/repo");
+        root.childs.push(Payload::new("api", "/repo/api/package.json"));
+
+        let output = Output::from_json(&render(&root, Format::Json, "/repo").unwrap()).unwrap();
+        assert_eq!(output.payload.name, "main");
+        assert_eq!(output.payload.childs[0].path, vec!["/api/package.json"]);
+    }
+}
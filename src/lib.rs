@@ -0,0 +1,265 @@
+pub mod payload;
+pub mod provider;
+pub mod rules;
+pub mod types;
+
+use payload::{helpers::find_edges_in_local_paths, payload::Payload};
+use provider::base::BaseProvider;
+use rules::registry::RuleRegistry;
+use rules::spec::nodejs::workspace::WorkspaceCache;
+
+pub struct AnalyserOptions<'a, P: BaseProvider> {
+    pub provider: P,
+    /// Rules to match against, e.g: `RuleRegistry::with_defaults()`
+    pub registry: &'a RuleRegistry,
+}
+
+pub fn analyser<P: BaseProvider>(opts: AnalyserOptions<P>) -> Payload {
+    let provider = opts.provider;
+    let mut pl = Payload::new("main", "/");
+    let _workspaces = WorkspaceCache::enable();
+
+    pl.recurse(&provider, &provider.base_path(), opts.registry);
+    find_edges_in_local_paths(&mut pl);
+
+    pl
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payload::payload::dependency;
+    use crate::provider::{fake::FakeProvider, fs::FsProvider};
+    use std::collections::HashMap;
+    #[test]
+    fn test_should_not_find_anything() {
+        let registry = RuleRegistry::with_defaults().unwrap();
+
+        let provider = FakeProvider::new(
+            HashMap::from_iter([("/".to_string(), vec![])]),
+            HashMap::new(),
+        );
+
+        let result = analyser(AnalyserOptions {
+            provider,
+            registry: &registry,
+        });
+
+        assert_eq!(result.name, "main");
+        assert!(result.path.contains("/"));
+        assert!(result.tech.is_none());
+        assert!(result.languages.is_empty());
+        assert!(result.childs.is_empty());
+        assert!(result.techs.is_empty());
+        assert!(result.dependencies.is_empty());
+        assert!(result.edges.is_empty());
+        assert!(result.reason.is_empty());
+    }
+
+    #[test]
+    fn test_should_match_full_paths_and_folders() {
+        let registry = RuleRegistry::with_defaults().unwrap();
+
+        let provider = FakeProvider::new(
+            HashMap::from_iter([
+                (
+                    "/".to_string(),
+                    vec![".circleci/".to_string(), ".terraform/".to_string()],
+                ),
+                ("/.circleci/".to_string(), vec!["config.yml".to_string()]),
+            ]),
+            HashMap::from_iter([("/.circleci/config.yml".to_string(), String::new())]),
+        );
+
+        let result = analyser(AnalyserOptions {
+            provider,
+            registry: &registry,
+        });
+
+        assert!(result.techs.contains("circleci"));
+        assert!(result.techs.contains("terraform"));
+        assert!(result.reason.contains("matched file: .circleci/config.yml"));
+    }
+
+    #[test]
+    fn test_should_register_component_of_same_tech() {
+        let docker_compose = r#"version: '3'
+services:
+  db:
+    container_name: db
+    image: postgres:15.1-alpine
+    ports:
+      - '5432:5432'
+    environment:
+      - POSTGRES_PASSWORD=postgres"#;
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+
+        let provider = FakeProvider::new(
+            HashMap::from_iter([(
+                "/".to_string(),
+                vec!["package.json".to_string(), "docker-compose.yml".to_string()],
+            )]),
+            HashMap::from_iter([
+                (
+                    "/docker-compose.yml".to_string(),
+                    docker_compose.to_string(),
+                ),
+                (
+                    "/package.json".to_string(),
+                    r#"{ "name": "test", "dependencies": {"pg": "1.0.0"}}"#.to_string(),
+                ),
+            ]),
+        );
+
+        let result = analyser(AnalyserOptions {
+            provider,
+            registry: &registry,
+        });
+        // println!("analyser result: {:#?}", result);
+        // Add assertions based on your actual implementation
+        assert_eq!(result.name, "main");
+        assert!(result.path.contains("/"));
+        assert_eq!(result.childs.len(), 2); // Should have two child nodes
+    }
+
+    #[test]
+    fn test_should_link_compose_build_context() {
+        let docker_compose = r#"services:
+  api:
+    build: ./api
+    depends_on: [db]
+  db:
+    image: postgres:15.1-alpine"#;
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+
+        let provider = FakeProvider::new(
+            HashMap::from_iter([
+                (
+                    "/".to_string(),
+                    vec!["docker-compose.yml".to_string(), "api/".to_string()],
+                ),
+                ("/api/".to_string(), vec!["package.json".to_string()]),
+            ]),
+            HashMap::from_iter([
+                (
+                    "/docker-compose.yml".to_string(),
+                    docker_compose.to_string(),
+                ),
+                (
+                    "/api/package.json".to_string(),
+                    r#"{ "name": "@acme/api", "dependencies": {"pg": "1.0.0"}}"#.to_string(),
+                ),
+            ]),
+        );
+
+        let result = analyser(AnalyserOptions {
+            provider,
+            registry: &registry,
+        });
+
+        let package = result.childs.iter().find(|c| c.name == "@acme/api").unwrap();
+        let service = result.childs.iter().find(|c| c.name == "api").unwrap();
+        let targets: Vec<&str> = service.edges.iter().map(|e| e.target.name.as_str()).collect();
+        assert_eq!(targets, vec!["db", "@acme/api"]);
+        assert_eq!(service.edges[1].target.id, package.id);
+    }
+
+    #[test]
+    fn test_should_link_cargo_workspace_members() {
+        let workspace = r#"
+[workspace]
+members = ["crates/*"]
+
+[workspace.dependencies]
+shared = { path = "crates/shared" }
+"#;
+        let api = r#"
+[package]
+name = "api"
+
+[dependencies]
+shared.workspace = true
+"#;
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+
+        let provider = FakeProvider::new(
+            HashMap::from_iter([
+                (
+                    "/".to_string(),
+                    vec!["Cargo.toml".to_string(), "crates/".to_string()],
+                ),
+                (
+                    "/crates/".to_string(),
+                    vec!["api/".to_string(), "shared/".to_string()],
+                ),
+                ("/crates/api/".to_string(), vec!["Cargo.toml".to_string()]),
+                (
+                    "/crates/shared/".to_string(),
+                    vec!["Cargo.toml".to_string()],
+                ),
+            ]),
+            HashMap::from_iter([
+                ("/Cargo.toml".to_string(), workspace.to_string()),
+                ("/crates/api/Cargo.toml".to_string(), api.to_string()),
+                (
+                    "/crates/shared/Cargo.toml".to_string(),
+                    "[package]\nname = \"shared\"".to_string(),
+                ),
+            ]),
+        );
+
+        let result = analyser(AnalyserOptions {
+            provider,
+            registry: &registry,
+        });
+
+        let names: Vec<&str> = result.childs.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["api", "shared"]);
+        let api = &result.childs[0];
+        assert_eq!(api.edges.len(), 1);
+        assert_eq!(api.edges[0].target.id, result.childs[1].id);
+    }
+
+    #[test]
+    fn test_should_link_npm_workspace_packages() {
+        let registry = RuleRegistry::with_defaults().unwrap();
+
+        let provider =
+            FsProvider::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/__fixtures__")).unwrap();
+
+        let result = analyser(AnalyserOptions {
+            provider,
+            registry: &registry,
+        });
+
+        let root = result.childs.iter().find(|c| c.name == "fake").unwrap();
+        assert!(root.reason.contains("monorepo: package.json"));
+        let api = root.childs.iter().find(|c| c.name == "@fake/api").unwrap();
+        let app = root.childs.iter().find(|c| c.name == "@fake/app").unwrap();
+        assert_eq!(app.edges.len(), 1);
+        assert_eq!(app.edges[0].target.id, api.id);
+    }
+
+    #[test]
+    fn test_should_analyse_a_local_folder() {
+        let registry = RuleRegistry::with_defaults().unwrap();
+
+        let provider = FsProvider::new(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/__fixtures__/rust"),
+        )
+        .unwrap();
+
+        let result = analyser(AnalyserOptions {
+            provider,
+            registry: &registry,
+        });
+
+        assert_eq!(result.name, "main");
+        let child = result.childs.iter().find(|c| c.name == "rust-server").unwrap();
+        assert!(child.path.iter().any(|p| p.ends_with("/Cargo.toml")));
+        assert!(child.dependencies.contains(&dependency("rust", "dotenv", "", "0.15.0", "")));
+    }
+}
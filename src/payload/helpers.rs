@@ -1,5 +1,9 @@
-use crate::payload::payload::Payload;
-use crate::rules::register::LIST_INDEXED;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::payload::payload::{Edge, Payload};
+use crate::provider::base::normalize_path;
+use crate::rules::registry::RuleRegistry;
 
 const NOT_A_COMPONENT: [&str; 4] = ["ci", "language", "tool", "framework"];
 
@@ -10,9 +14,13 @@ const NOT_A_COMPONENT: [&str; 4] = ["ci", "language", "tool", "framework"];
 ///  - Create an eponymous component
 ///
 /// Obviously there could be some false positive.
-pub fn find_implicit_component(pl: &mut Payload, tech: &str, reason: &[String]) {
-    let list_indexed = LIST_INDEXED.lock().unwrap();
-    let ref_rule = match list_indexed.get(tech) {
+pub fn find_implicit_component(
+    pl: &mut Payload,
+    tech: &str,
+    reason: &[String],
+    registry: &RuleRegistry,
+) {
+    let ref_rule = match registry.get(tech) {
         Some(rule) => rule,
         None => return,
     };
@@ -32,12 +40,11 @@ pub fn find_implicit_component(pl: &mut Payload, tech: &str, reason: &[String])
 /// For example we receive:
 ///  - "vercel" from a folder named .vercel
 ///  - we deduct this component is hosted by Vercel
-///  - Create an eponymous component
+///  - Create an eponymous component, or reuse the one found by find_implicit_component
 ///
 /// Obviously there could be some false positive.
-pub fn find_hosting(pl: &mut Payload, tech: &str) {
-    let list_indexed = LIST_INDEXED.lock().unwrap();
-    let ref_rule = match list_indexed.get(tech) {
+pub fn find_hosting(pl: &mut Payload, tech: &str, registry: &RuleRegistry) {
+    let ref_rule = match registry.get(tech) {
         Some(rule) => rule,
         None => return,
     };
@@ -45,11 +52,40 @@ pub fn find_hosting(pl: &mut Payload, tech: &str) {
     if ref_rule.r#type != "hosting" && ref_rule.r#type != "cloud" {
         return;
     }
+    // Nothing is hosted in itself
+    if pl.tech.as_deref() == Some(tech) {
+        return;
+    }
 
-    let find = pl.childs.iter().find(|c| c.tech.as_ref() == Some(&ref_rule.tech));
-    if find.is_none() {
-        panic!("cant find hosting {}", ref_rule.tech);
+    // A more specific hosting wins over a cloud provider
+    let replace = match &pl.in_component {
+        None => true,
+        Some(current) => {
+            ref_rule.r#type == "hosting"
+                && current
+                    .tech
+                    .as_ref()
+                    .and_then(|t| registry.get(t))
+                    .is_some_and(|rule| rule.r#type == "cloud")
+        }
+    };
+    if !replace {
+        return;
     }
+
+    let idx = match pl.childs.iter().position(|c| c.tech.as_deref() == Some(tech)) {
+        Some(idx) => idx,
+        None => {
+            // The set has no order, the smallest path keeps the result stable between runs
+            let path = pl.path.iter().min().cloned().unwrap_or_default();
+            let mut comp = Payload::new(&ref_rule.name, &path);
+            comp.tech = Some(tech.to_string());
+            pl.childs.push(comp);
+            pl.childs.len() - 1
+        }
+    };
+
+    pl.in_component = Some(Box::new(reference(&pl.childs[idx])));
 }
 
 /// Some edges can be found in the dependencies, i.e:
@@ -57,45 +93,109 @@ pub fn find_hosting(pl: &mut Payload, tech: &str) {
 ///
 /// We try to find those import, using only the dependencies (not opening the code),
 /// it can lead to some false positive with very generic names.
-
 pub fn find_edges_in_dependencies(pl: &mut Payload) {
-    // First collect all names into a HashSet
-    let names: std::collections::HashSet<String> =
-        pl.childs.iter().map(|child| child.name.clone()).collect();
-
-    // Create a Vec to store the edges we want to add
-    let mut edges_to_add = Vec::new();
+    let targets: Vec<Payload> = pl.childs.iter().map(reference).collect();
 
-    // First pass: collect all edges we want to add
-    for child in &pl.childs {
+    for child in &mut pl.childs {
         for dep in &child.dependencies {
-            let name = &dep[1];
-            if !names.contains(name) {
+            let Some(name) = dep.get(1) else {
                 continue;
-            }
+            };
 
             // Self referencing
-            if name == &child.name || child.tech.as_ref().map_or(false, |tech| name == tech) {
+            if name == &child.name || child.tech.as_ref() == Some(name) {
                 continue;
             }
 
+            let Some(target) = targets.iter().find(|t| t.name == *name) else {
+                continue;
+            };
             // Check if we already added an edge about that
-            if let Some(target) = pl.childs.iter().find(|c| c.name == *name) {
-                // Store the edge information for later processing
-                edges_to_add.push((child.id.clone(), target.id.clone()));
+            if child.edges.iter().any(|edge| edge.target.id == target.id) {
+                continue;
+            }
+
+            // Importing a package only reads from it
+            child.edges.push(Edge {
+                target: Box::new(target.clone()),
+                read: true,
+                write: false,
+            });
+        }
+    }
+}
+
+/// Copy of a Payload to reference it from another one (edge, in_component),
+/// without what it contains.
+pub fn reference(pl: &Payload) -> Payload {
+    let mut target = pl.clone();
+    target.childs.clear();
+    target.edges.clear();
+    target.parent = None;
+    target.in_component = None;
+    target
+}
+
+/// Local dependencies (`path:./api`) point to another component of the same repository,
+/// e.g: a docker-compose service built from a folder.
+///
+/// The target can be anywhere in the tree, so this runs once everything was detected.
+pub fn find_edges_in_local_paths(pl: &mut Payload) {
+    let mut folders: Vec<(PathBuf, Payload)> = Vec::new();
+    collect_folders(pl, &mut folders);
+
+    // Components found from a manifest first, implicit ones share the folder of their parent
+    folders.sort_by_key(|(_, target)| target.tech.is_some());
+
+    link_local_paths(pl, &folders);
+}
+
+fn collect_folders(pl: &Payload, folders: &mut Vec<(PathBuf, Payload)>) {
+    for child in &pl.childs {
+        let target = reference(child);
+        for folder in payload_folders(child) {
+            folders.push((folder, target.clone()));
+        }
+        collect_folders(child, folders);
+    }
+}
+
+fn link_local_paths(pl: &mut Payload, folders: &[(PathBuf, Payload)]) {
+    if let Some(base) = payload_folders(pl).into_iter().next() {
+        for dep in pl.dependencies.clone() {
+            let Some(local) = dep.get(2).and_then(|version| version.strip_prefix("path:")) else {
+                continue;
+            };
+
+            let folder = normalize_path(&base.join(local));
+            let target =
+                folders.iter().find(|(path, target)| *path == folder && target.id != pl.id);
+            if let Some((_, target)) = target {
+                if !pl.edges.iter().any(|edge| edge.target.id == target.id) {
+                    pl.add_edges(target.clone());
+                }
             }
         }
     }
 
-    // Second pass: add all the edges
-    for (source_id, target_id) in edges_to_add {
-        // Add edge logic would go here
-        // You'll need to implement the actual edge adding functionality
-        // This could be something like:
-        // pl.add_edge(&source_id, &target_id);
+    for child in &mut pl.childs {
+        link_local_paths(child, folders);
     }
 }
 
+/// Folders of the files a Payload was found in, sorted
+fn payload_folders(pl: &Payload) -> Vec<PathBuf> {
+    let mut folders: Vec<PathBuf> = pl
+        .path
+        .iter()
+        .filter_map(|fp| Path::new(fp).parent())
+        .map(normalize_path)
+        .collect();
+    folders.sort();
+    folders.dedup();
+    folders
+}
+
 /// flatten takes a nested Payload and brings everything down to a single level.
 /// It deduplicates components that are strictly similar, and keep references in path.
 ///
@@ -106,14 +206,17 @@ pub fn flatten(src: &Payload, merge: bool) -> Payload {
     let mut dest = Payload::new("flatten", "/");
     push_childs(src, &mut dest);
 
-    // Find and merge duplicates
-    let mut duplicates = Vec::new();
+    // Find and merge duplicates, by id: the id of the payload it was merged into
+    let mut duplicates: HashMap<String, String> = HashMap::new();
     for i in 0..dest.childs.len() {
-        if duplicates.contains(&dest.childs[i].id) {
+        if duplicates.contains_key(&dest.childs[i].id) {
             continue;
         }
 
         for j in (i + 1)..dest.childs.len() {
+            if duplicates.contains_key(&dest.childs[j].id) {
+                continue;
+            }
             if dest.childs[i].tech.is_none() || dest.childs[j].tech.is_none() {
                 continue;
             }
@@ -123,20 +226,54 @@ pub fn flatten(src: &Payload, merge: bool) -> Payload {
                 continue;
             }
 
-            duplicates.push(dest.childs[j].id.clone());
-            // Combine logic would go here
-            // Note: The original combine functionality needs to be implemented in the Payload struct
+            duplicates.insert(dest.childs[j].id.clone(), dest.childs[i].id.clone());
+            let duplicate = dest.childs[j].clone();
+            dest.childs[i].combine(&duplicate);
         }
     }
 
     // Remove duplicates
-    dest.childs.retain(|child| !duplicates.contains(&child.id));
+    dest.childs.retain(|child| !duplicates.contains_key(&child.id));
+
+    // Edges to a duplicate now point to the payload it was merged into, same for hosting
+    let targets: HashMap<String, Payload> =
+        dest.childs.iter().map(|child| (child.id.clone(), reference(child))).collect();
+    for child in &mut dest.childs {
+        if let Some(hosting) = &child.in_component {
+            let id = duplicates.get(&hosting.id).unwrap_or(&hosting.id);
+            child.in_component = targets.get(id).map(|t| Box::new(t.clone()));
+        }
+
+        let edges = std::mem::take(&mut child.edges);
+        for mut edge in edges {
+            let id = duplicates.get(&edge.target.id).unwrap_or(&edge.target.id);
+            if let Some(target) = targets.get(id) {
+                edge.target = Box::new(target.clone());
+            }
+            if edge.target.id == child.id
+                || child.edges.iter().any(|e| e.target.id == edge.target.id)
+            {
+                continue;
+            }
+            child.edges.push(edge);
+        }
+    }
 
     find_edges_in_dependencies(&mut dest);
 
     if merge {
-        // Merge logic would go here
-        // Note: The original merge functionality needs to be implemented in the Payload struct
+        // Everything found at the root level too, not only in its childs
+        let mut root = src.clone();
+        root.childs.clear();
+        root.edges.clear();
+        dest.combine(&root);
+
+        let childs = dest.childs.clone();
+        for child in &childs {
+            dest.combine(&reference(child));
+        }
+        dest.path = HashSet::from(["/".to_string()]);
+        dest.reason.clear();
     }
 
     dest
@@ -154,6 +291,177 @@ fn push_childs(src: &Payload, dest: &mut Payload) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::payload::payload::dependency;
+    use crate::provider::fs::FsProvider;
+    use crate::{analyser, AnalyserOptions};
+
+    fn dep(name: &str) -> Vec<String> {
+        dependency("npm", name, "", "1.0.0", "")
+    }
+
+    #[test]
+    fn test_find_hosting() {
+        let registry = RuleRegistry::with_defaults().unwrap();
+
+        // No implicit component yet, it's created instead of panicking
+        let mut app = Payload::new("app", "/app/package.json");
+        app.path.insert("/app/package-lock.json".to_string());
+        app.path.insert("/app/yarn.lock".to_string());
+        find_hosting(&mut app, "gcp", &registry);
+        assert_eq!(app.childs.len(), 1);
+        let path: Vec<&String> = app.childs[0].path.iter().collect();
+        assert_eq!(path, vec!["/app/package-lock.json"]);
+        assert_eq!(app.in_component.as_ref().unwrap().id, app.childs[0].id);
+
+        // A hosting is more specific than a cloud, and reuses the existing child
+        let mut vercel = Payload::new("Vercel", "/app/package.json");
+        vercel.tech = Some("vercel".to_string());
+        let vercel_id = vercel.id.clone();
+        app.add_child(vercel);
+        find_hosting(&mut app, "vercel", &registry);
+        assert_eq!(app.childs.len(), 2);
+        assert_eq!(app.in_component.as_ref().unwrap().id, vercel_id);
+
+        find_hosting(&mut app, "aws", &registry);
+        assert_eq!(app.in_component.as_ref().unwrap().id, vercel_id);
+
+        // Not a hosting, or hosted in itself
+        let mut db = Payload::new("db", "/docker-compose.yml");
+        find_hosting(&mut db, "postgresql", &registry);
+        find_hosting(&mut db, "unknown", &registry);
+        assert!(db.in_component.is_none());
+        let mut hosting = Payload::new("Fly.io", "/fly.toml");
+        hosting.tech = Some("flyio".to_string());
+        find_hosting(&mut hosting, "flyio", &registry);
+        assert!(hosting.in_component.is_none());
+        assert!(hosting.childs.is_empty());
+    }
+
+    #[test]
+    fn test_find_hosting_in_folder() {
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let provider = FsProvider::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/__fixtures__/pkgs"
+        ))
+        .unwrap();
+
+        let result = analyser(AnalyserOptions {
+            provider,
+            registry: &registry,
+        });
+
+        // Found with the .vercel folder
+        let app = result.childs.iter().find(|c| c.name == "@fake/app").unwrap();
+        let vercel = app.childs.iter().find(|c| c.tech.as_deref() == Some("vercel")).unwrap();
+        assert_eq!(app.in_component.as_ref().unwrap().id, vercel.id);
 
-    // Add tests here
+        let api = result.childs.iter().find(|c| c.name == "@fake/api").unwrap();
+        assert!(api.in_component.is_none());
+
+        // Kept when flattened
+        let flat = flatten(&result, false);
+        let app = flat.childs.iter().find(|c| c.name == "@fake/app").unwrap();
+        assert!(flat
+            .childs
+            .iter()
+            .any(|c| Some(&c.id) == app.in_component.as_ref().map(|h| &h.id)));
+    }
+
+    #[test]
+    fn test_find_edges_in_dependencies() {
+        let mut root = Payload::new("main", "/");
+        let mut app = Payload::new("app", "/app/package.json");
+        app.dependencies = vec![dep("api"), dep("api"), dep("app"), dep("react")];
+        let mut api = Payload::new("api", "/api/package.json");
+        api.dependencies = vec![dep("pg")];
+        root.add_child(app);
+        root.add_child(api);
+
+        find_edges_in_dependencies(&mut root);
+        // Running it twice does not duplicate anything
+        find_edges_in_dependencies(&mut root);
+
+        let app = &root.childs[0];
+        assert_eq!(app.edges.len(), 1);
+        assert_eq!(app.edges[0].target.id, root.childs[1].id);
+        assert!(app.edges[0].read);
+        assert!(!app.edges[0].write);
+        assert!(root.childs[1].edges.is_empty());
+    }
+
+    #[test]
+    fn test_flatten_edges_in_monorepo() {
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let provider = FsProvider::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/__fixtures__/pkgs"
+        ))
+        .unwrap();
+
+        let result = analyser(AnalyserOptions {
+            provider,
+            registry: &registry,
+        });
+        let flat = flatten(&result, false);
+
+        let app = flat.childs.iter().find(|c| c.name == "@fake/app").unwrap();
+        let api = flat.childs.iter().find(|c| c.name == "@fake/api").unwrap();
+        assert_eq!(app.edges.len(), 1);
+        assert_eq!(app.edges[0].target.id, api.id);
+        assert!(api.edges.is_empty());
+    }
+
+    #[test]
+    fn test_flatten_combine_duplicates() {
+        let mut root = Payload::new("main", "/");
+        root.languages.insert("YAML".to_string(), 1);
+
+        let mut db = Payload::new("db", "/docker-compose.yml");
+        db.tech = Some("postgresql".to_string());
+        let db_id = db.id.clone();
+
+        let mut api = Payload::new("api", "/api/package.json");
+        api.languages.insert("TypeScript".to_string(), 2);
+        api.dependencies = vec![dep("pg")];
+        let mut postgres = Payload::new("Postgres", "/api/package.json");
+        postgres.tech = Some("postgresql".to_string());
+        postgres.techs.insert("postgresql".to_string());
+        api.add_edges(postgres.clone());
+        api.add_child(postgres);
+
+        let mut app = Payload::new("app", "/app/package.json");
+        app.languages.insert("TypeScript".to_string(), 3);
+        app.dependencies = vec![dep("api"), dep("pg")];
+
+        root.add_child(db);
+        root.add_child(api);
+        root.add_child(app);
+
+        let flat = flatten(&root, false);
+        let names: Vec<&str> = flat.childs.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["db", "api", "app"]);
+
+        let db = &flat.childs[0];
+        assert_eq!(db.path.len(), 2);
+        assert!(db.techs.contains("postgresql"));
+
+        // The edge to the duplicate was moved to the payload it was merged into
+        let api = &flat.childs[1];
+        assert_eq!(api.edges.len(), 1);
+        assert_eq!(api.edges[0].target.id, db_id);
+        assert_eq!(flat.childs[2].edges[0].target.name, "api");
+
+        // Nothing is rolled up without merge
+        assert!(flat.languages.is_empty());
+        assert!(flat.dependencies.is_empty());
+
+        let merged = flatten(&root, true);
+        assert_eq!(merged.childs.len(), 3);
+        assert_eq!(merged.languages.get("TypeScript"), Some(&5));
+        assert_eq!(merged.languages.get("YAML"), Some(&1));
+        assert!(merged.techs.contains("postgresql"));
+        assert_eq!(merged.dependencies, vec![dep("pg"), dep("api")]);
+        assert!(merged.edges.is_empty());
+    }
 }
@@ -48,10 +48,7 @@ pub fn detect_lang(filename: &str) -> Option<LangListItem> {
         .and_then(|os_str| os_str.to_str())
         .map(|s| format!(".{}", s));
 
-    let ext = match ext {
-        Some(e) => e,
-        None => return None,
-    };
+    let ext = ext?;
 
     languages()
         .into_iter()
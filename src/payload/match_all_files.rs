@@ -1,27 +1,37 @@
 use crate::provider::base::ProviderFile;
-use crate::rules::loader::{RULES_EXTENSIONS, RULES_TECHS};
+use crate::rules::registry::RuleRegistry;
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
-pub fn match_all_files(files: &[ProviderFile], base_path: &str) -> HashMap<String, Vec<String>> {
+pub fn match_all_files(
+    files: &[ProviderFile],
+    base_path: &str,
+    registry: &RuleRegistry,
+) -> HashMap<String, Vec<String>> {
     let mut matched: HashMap<String, Vec<String>> = HashMap::new();
 
+    // Full path rules match the path relative to the root of the analysis
+    let relative: Vec<ProviderFile> = files
+        .iter()
+        .map(|file| ProviderFile {
+            fp: Path::new(&file.fp)
+                .strip_prefix(base_path)
+                .map(|fp| fp.to_string_lossy().to_string())
+                .unwrap_or_else(|_| file.fp.clone()),
+            ..file.clone()
+        })
+        .collect();
+
     // Match files
-    let rules_techs = RULES_TECHS.lock().unwrap();
-    for rule in rules_techs.iter() {
-        if let Ok((rule, path)) = rule(files.to_vec()) {
-            let path_display = path.replace(base_path, "");
-            matched.insert(rule.tech, vec![format!("matched file: {}", path_display)]);
+    for rule in registry.rules_techs.iter() {
+        if let Ok((rule, path)) = rule(relative.clone()) {
+            matched.insert(rule.tech, vec![format!("matched file: {}", path)]);
         }
     }
 
     // Match extensions
     let mut exts = HashSet::new();
     for file in files {
-        println!("File name: {}", file.name);
-        let extension = Path::new(&file.name).extension();
-        println!("Extension: {:?}", extension);
-
         if let Some(ext) = Path::new(&file.name).extension() {
             if let Some(ext_str) = ext.to_str() {
                 exts.insert(ext_str.to_string());
@@ -29,42 +39,28 @@ pub fn match_all_files(files: &[ProviderFile], base_path: &str) -> HashMap<Strin
         }
     }
 
-    let rules_extensions = RULES_EXTENSIONS.lock().unwrap();
-    for rule in rules_extensions.iter() {
+    for rule in registry.rules_extensions.iter() {
         if let Ok((rule, ext)) = rule(exts.clone()) {
-            if !matched.contains_key(&rule.tech) {
-                matched.insert(rule.tech, vec![format!("matched extension: {}", ext)]);
-            }
+            matched
+                .entry(rule.tech)
+                .or_insert_with(|| vec![format!("matched extension: {}", ext)]);
         }
     }
-    println!("match_all_files matched: {:?}", matched);
+    log::debug!("match_all_files matched: {:?}", matched);
     matched
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        provider::base::FileType,
-        rules::{
-            loader::load_all_rules,
-            register::{register_all, REGISTERED_RULES},
-        },
-    };
+    use crate::provider::base::FileType;
 
     #[test]
     fn test_match_all_files() {
-        register_all().unwrap();
-        let registered_rules = {
-            let rules = REGISTERED_RULES.lock().unwrap();
-            rules.clone()
-        };
-        println!("Registered rules: {:#?}", registered_rules);
-
-        load_all_rules(&registered_rules);
+        let registry = RuleRegistry::with_defaults().unwrap();
 
         // Empty case
-        let res = match_all_files(&[], "/");
+        let res = match_all_files(&[], "/", &registry);
         assert!(res.is_empty());
 
         // Extension based matching
@@ -73,9 +69,7 @@ mod tests {
             fp: "/index.tsx".to_string(),
             file_type: FileType::File,
         }];
-        let res = match_all_files(&files, "/");
-
-        println!("Matched rules: {:?}", res.keys().collect::<Vec<_>>());
+        let res = match_all_files(&files, "/", &registry);
 
         assert!(res.contains_key("typescript"));
         assert!(res.contains_key("react"));
@@ -94,11 +88,32 @@ mod tests {
             fp: "/package.json".to_string(),
             file_type: FileType::File,
         }];
-        let res = match_all_files(&files, "/");
+        let res = match_all_files(&files, "/", &registry);
         assert!(res.contains_key("nodejs"));
         assert_eq!(
             res.get("nodejs").unwrap(),
             &vec!["matched file: package.json".to_string()]
         );
+
+        // Full path, relative to the root
+        let files = vec![ProviderFile {
+            name: "config.yml".to_string(),
+            fp: "/repo/.circleci/config.yml".to_string(),
+            file_type: FileType::File,
+        }];
+        let res = match_all_files(&files, "/repo", &registry);
+        assert_eq!(
+            res.get("circleci").unwrap(),
+            &vec!["matched file: .circleci/config.yml".to_string()]
+        );
+        assert!(match_all_files(&files, "/repo/.circleci", &registry).is_empty());
+
+        // Folder name
+        let files = vec![ProviderFile {
+            name: ".vercel".to_string(),
+            fp: "/.vercel".to_string(),
+            file_type: FileType::Dir,
+        }];
+        assert!(match_all_files(&files, "/", &registry).contains_key("vercel"));
     }
 }
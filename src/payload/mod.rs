@@ -1,4 +1,6 @@
 pub mod helpers;
 pub mod languages;
 pub mod match_all_files;
+pub mod output;
+#[allow(clippy::module_inception)]
 pub mod payload;
@@ -0,0 +1,311 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use super::payload::{Edge, Payload};
+
+/// Version of the JSON schema, bumped on any breaking change of the fields below.
+pub const OUTPUT_VERSION: u32 = 1;
+
+/// Serializable result of an analysis.
+///
+/// Unlike Payload, it only contains plain data: edges point to their target by id,
+/// sets are sorted, and nothing refers to the parent or the rules used.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+    pub version: u32,
+    #[serde(flatten)]
+    pub payload: PayloadOutput,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PayloadOutput {
+    pub id: String,
+    pub name: String,
+    pub path: Vec<String>,
+    pub tech: Option<String>,
+    pub techs: Vec<String>,
+    pub languages: BTreeMap<String, i32>,
+    pub dependencies: Vec<Vec<String>>,
+    pub edges: Vec<EdgeOutput>,
+    /// Id of the payload hosting this one
+    #[serde(default)]
+    pub in_component: Option<String>,
+    pub reason: Vec<String>,
+    pub childs: Vec<PayloadOutput>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EdgeOutput {
+    pub target: String,
+    pub read: bool,
+    pub write: bool,
+}
+
+impl Output {
+    pub fn new(pl: &Payload) -> Self {
+        Self {
+            version: OUTPUT_VERSION,
+            payload: PayloadOutput::from(pl),
+        }
+    }
+
+    /// Make every path relative to `base_path`, so the output does not depend on where it ran.
+    pub fn relative_to(mut self, base_path: &str) -> Self {
+        self.payload.relative_to(base_path);
+        self
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        let output: Output = serde_json::from_str(content).map_err(|e| e.to_string())?;
+        if output.version != OUTPUT_VERSION {
+            return Err(format!(
+                "unsupported output version {} (expected {})",
+                output.version, OUTPUT_VERSION
+            ));
+        }
+
+        Ok(output)
+    }
+
+    /// Rebuild the Payload tree, resolving edges to the payload they target.
+    pub fn into_payload(self) -> Result<Payload, String> {
+        let mut index = HashMap::new();
+        index_payloads(&self.payload, &mut index);
+
+        build_payload(&self.payload, &index)
+    }
+}
+
+impl From<&Payload> for PayloadOutput {
+    fn from(pl: &Payload) -> Self {
+        let mut path: Vec<String> = pl.path.iter().cloned().collect();
+        path.sort();
+        let mut techs: Vec<String> = pl.techs.iter().cloned().collect();
+        techs.sort();
+        let mut reason: Vec<String> = pl.reason.iter().cloned().collect();
+        reason.sort();
+        let mut dependencies = pl.dependencies.clone();
+        dependencies.sort();
+
+        Self {
+            id: pl.id.clone(),
+            name: pl.name.clone(),
+            path,
+            tech: pl.tech.clone(),
+            techs,
+            languages: pl.languages.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+            dependencies,
+            edges: pl
+                .edges
+                .iter()
+                .map(|edge| EdgeOutput {
+                    target: edge.target.id.clone(),
+                    read: edge.read,
+                    write: edge.write,
+                })
+                .collect(),
+            in_component: pl.in_component.as_ref().map(|c| c.id.clone()),
+            reason,
+            childs: pl.childs.iter().map(PayloadOutput::from).collect(),
+        }
+    }
+}
+
+impl PayloadOutput {
+    fn relative_to(&mut self, base_path: &str) {
+        for path in self.path.iter_mut() {
+            *path = path_in_base(path, base_path);
+        }
+        self.path.sort();
+        self.path.dedup();
+
+        for child in self.childs.iter_mut() {
+            child.relative_to(base_path);
+        }
+    }
+}
+
+/// Express `path` from the root of `base_path`, always starting with a slash.
+/// A path outside of `base_path` is kept as is.
+pub fn path_in_base(path: &str, base_path: &str) -> String {
+    match Path::new(path).strip_prefix(base_path) {
+        Ok(relative) => format!("/{}", relative.to_string_lossy()),
+        Err(_) => path.to_string(),
+    }
+}
+
+fn index_payloads<'a>(pl: &'a PayloadOutput, index: &mut HashMap<String, &'a PayloadOutput>) {
+    index.insert(pl.id.clone(), pl);
+    for child in &pl.childs {
+        index_payloads(child, index);
+    }
+}
+
+/// Payload without its edges nor childs, used as the target of an edge or as in_component,
+/// like `helpers::reference`.
+fn build_node(pl: &PayloadOutput) -> Payload {
+    let mut node = Payload::new(&pl.name, "");
+    node.id = pl.id.clone();
+    node.path = pl.path.iter().cloned().collect();
+    node.tech = pl.tech.clone();
+    node.techs = pl.techs.iter().cloned().collect();
+    node.languages = pl.languages.iter().map(|(k, v)| (k.clone(), *v)).collect();
+    node.dependencies = pl.dependencies.clone();
+    node.reason = pl.reason.iter().cloned().collect();
+    node
+}
+
+fn build_payload(
+    pl: &PayloadOutput,
+    index: &HashMap<String, &PayloadOutput>,
+) -> Result<Payload, String> {
+    let mut node = build_node(pl);
+
+    node.edges = pl
+        .edges
+        .iter()
+        .map(|edge| {
+            let target = index
+                .get(&edge.target)
+                .ok_or_else(|| format!("unknown edge target {} in {}", edge.target, pl.id))?;
+            Ok(Edge {
+                target: Box::new(build_node(target)),
+                read: edge.read,
+                write: edge.write,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    if let Some(id) = &pl.in_component {
+        let hosting = index
+            .get(id)
+            .ok_or_else(|| format!("unknown in_component {} in {}", id, pl.id))?;
+        node.in_component = Some(Box::new(build_node(hosting)));
+    }
+
+    node.childs = pl
+        .childs
+        .iter()
+        .map(|child| build_payload(child, index))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payload::payload::dependency;
+    use crate::rules::registry::RuleRegistry;
+
+    fn setup_payload() -> Payload {
+        let mut root = Payload::new("main", "/");
+        root.add_lang("Rust", 2, &RuleRegistry::new());
+
+        let mut db = Payload::new("db", "/docker-compose.yml");
+        db.tech = Some("postgresql".to_string());
+        db.reason.insert("matched: postgres".to_string());
+        db.dependencies.push(dependency("docker", "postgres", "", "15", ""));
+
+        let mut api = Payload::new("api", "/api/package.json");
+        api.path.insert("/api2/package.json".to_string());
+        api.techs.insert("nodejs".to_string());
+        api.add_edges(db.clone());
+        api.in_component = Some(Box::new(db.clone()));
+
+        root.childs.push(db);
+        root.childs.push(api);
+        root
+    }
+
+    #[test]
+    fn test_output_json_schema() {
+        let root = setup_payload();
+        let json: serde_json::Value =
+            serde_json::from_str(&Output::new(&root).to_json().unwrap()).unwrap();
+
+        assert_eq!(json["version"], OUTPUT_VERSION);
+        assert_eq!(json["name"], "main");
+        assert_eq!(json["languages"]["Rust"], 2);
+        assert_eq!(json["childs"][0]["tech"], "postgresql");
+        assert_eq!(
+            json["childs"][1]["path"],
+            serde_json::json!(["/api/package.json", "/api2/package.json"])
+        );
+        assert_eq!(
+            json["childs"][1]["edges"],
+            serde_json::json!([{ "target": root.childs[0].id, "read": true, "write": true }])
+        );
+        assert_eq!(json["childs"][1]["in_component"], root.childs[0].id);
+        assert!(json["childs"][0]["in_component"].is_null());
+        assert!(json.get("parent").is_none());
+        assert!(json.get("components").is_none());
+    }
+
+    #[test]
+    fn test_output_roundtrip() {
+        let mut root = setup_payload();
+        root.childs[0].childs.push(Payload::new("replica", "/docker-compose.yml"));
+        let output = Output::new(&root);
+
+        let reloaded = Output::from_json(&output.to_json().unwrap()).unwrap();
+        assert_eq!(reloaded, output);
+
+        let pl = reloaded.into_payload().unwrap();
+        assert_eq!(pl.id, root.id);
+        assert_eq!(pl.childs.len(), 2);
+        assert_eq!(pl.childs[0].dependencies, root.childs[0].dependencies);
+        assert_eq!(pl.childs[1].path, root.childs[1].path);
+        assert_eq!(pl.childs[1].edges.len(), 1);
+        assert_eq!(pl.childs[1].edges[0].target.id, root.childs[0].id);
+        assert_eq!(pl.childs[1].edges[0].target.name, "db");
+        // Targets are references, the tree is only rebuilt once
+        assert_eq!(pl.childs[0].childs.len(), 1);
+        assert!(pl.childs[1].edges[0].target.childs.is_empty());
+        assert!(pl.childs[1].in_component.as_ref().unwrap().childs.is_empty());
+        assert_eq!(
+            pl.childs[1].in_component.as_ref().unwrap().id,
+            root.childs[0].id
+        );
+        assert_eq!(Output::new(&pl), output);
+    }
+
+    #[test]
+    fn test_output_errors() {
+        let mut output = Output::new(&setup_payload());
+        output.version = OUTPUT_VERSION + 1;
+        assert!(Output::from_json(&output.to_json().unwrap()).is_err());
+        assert!(Output::from_json("{}").is_err());
+
+        let mut output = Output::new(&setup_payload());
+        output.payload.childs.remove(0);
+        assert!(output.into_payload().is_err());
+    }
+
+    #[test]
+    fn test_output_relative_to() {
+        let mut root = Payload::new("main", "/repo");
+        root.childs.push(Payload::new("api", "/repo/api/package.json"));
+
+        let output = Output::new(&root).relative_to("/repo");
+        assert_eq!(output.payload.path, vec!["/"]);
+        assert_eq!(output.payload.childs[0].path, vec!["/api/package.json"]);
+    }
+
+    #[test]
+    fn test_path_in_base() {
+        assert_eq!(
+            path_in_base("/repo/api/package.json", "/repo"),
+            "/api/package.json"
+        );
+        assert_eq!(path_in_base("/repo/api", "/repo/"), "/api");
+        assert_eq!(path_in_base("/repo", "/repo"), "/");
+        // Only whole folders are stripped
+        assert_eq!(path_in_base("/repo2/x", "/repo"), "/repo2/x");
+    }
+}
@@ -1,12 +1,11 @@
 use crate::{
     provider::base::{BaseProvider, FileType, IGNORED_DIVE_PATHS},
-    rules::{loader::RULES_COMPONENTS, register::NAME_TO_KEY},
-    types::rule::ComponentMatcher,
+    rules::registry::RuleRegistry,
 };
 use std::collections::{HashMap, HashSet};
 
 use super::{
-    helpers::{find_hosting, find_implicit_component},
+    helpers::{find_hosting, find_implicit_component, reference},
     languages::detect_lang,
     match_all_files::match_all_files,
 };
@@ -20,11 +19,13 @@ pub struct Payload {
     pub languages: HashMap<String, i32>,
     pub childs: Vec<Payload>,
     pub techs: HashSet<String>,
+    /// Every dependency found, as built by [`dependency`]
     pub dependencies: Vec<Vec<String>>,
     pub edges: Vec<Edge>,
     pub parent: Option<Box<Payload>>,
     pub reason: HashSet<String>,
-    pub components: Vec<ComponentMatcher>, // Add this new field
+    /// Where this payload is hosted, e.g: the Vercel component for a folder with `.vercel`
+    pub in_component: Option<Box<Payload>>,
 }
 
 #[derive(Debug, Clone)]
@@ -34,11 +35,30 @@ pub struct Edge {
     pub write: bool,
 }
 
+/// Dependencies are always `[type, name, resolved, declared, scope]`, followed by attributes:
+/// - `resolved`: exact version in use, e.g: from a lockfile, or `path:<folder>` for a package of
+///   the same repository. Empty when unknown.
+/// - `declared`: version as written in the manifest, e.g: `^1.2.0`, `git:<url>#<ref>` or
+///   `path:<folder>`, `latest` when not constrained. Empty for transitive dependencies.
+/// - `scope`: empty when used at runtime, otherwise `dev`, `build`, `optional` or `transitive`.
+/// - attributes: `key:value` details specific to an ecosystem, e.g: `features:derive,rc`.
+pub fn dependency(
+    r#type: &str,
+    name: &str,
+    resolved: &str,
+    declared: &str,
+    scope: &str,
+) -> Vec<String> {
+    [r#type, name, resolved, declared, scope]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
 impl Payload {
     pub fn new(name: &str, folder_path: &str) -> Self {
         let mut path = HashSet::new();
         path.insert(folder_path.to_string());
-        let components = RULES_COMPONENTS.lock().unwrap().clone();
         Self {
             id: generate_id(),
             name: name.to_string(),
@@ -51,48 +71,68 @@ impl Payload {
             edges: Vec::new(),
             parent: None,
             reason: HashSet::new(),
-            components,
+            in_component: None,
         }
     }
 
-    pub fn recurse<P: BaseProvider>(&mut self, provider: &P, file_path: &str) {
+    pub fn recurse<P: BaseProvider>(
+        &mut self,
+        provider: &P,
+        file_path: &str,
+        registry: &RuleRegistry,
+    ) {
         let files = provider.list_dir(file_path);
-        let mut ctx = self.clone();
-        let components = self.components.clone();
-        for rule in &components {
-            let res = rule(&files, provider);
+        // Index of the child found at this level, everything below is attached to it
+        let mut ctx_idx = None;
+        let mut virtuals = Vec::new();
+        for rule in &registry.rules_components {
+            let res = rule(&files, provider, registry);
             let payloads = match res {
                 Ok(payload) => vec![payload],
-                Err(_) => {
-                    println!("no match res");
-                    continue;
-                }
+                Err(_) => continue,
             };
-            // println!("Recurse Payloads: {:?}", payloads);
             for pl in payloads {
                 if pl.name != "virtual" {
-                    ctx = pl.clone();
-                    self.add_child(pl);
+                    ctx_idx = Some(self.add_child_idx(pl));
                 } else {
-                    self.combine_dependencies(&pl);
-                    for child in pl.childs {
-                        self.add_child(child);
-                    }
+                    virtuals.push(pl);
                 }
             }
         }
 
-        // println!("Recurse provider: {:#?} - {:?}", &provider, ctx.id);
+        // Virtual payloads describe the component of this folder (whichever rule found it),
+        // their own childs are siblings
+        for pl in &mut virtuals {
+            for child in std::mem::take(&mut pl.childs) {
+                let id = child.id.clone();
+                let idx = self.add_child_idx(child);
+                // The child may have been merged into an existing one
+                if pl.in_component.as_ref().is_some_and(|c| c.id == id) {
+                    pl.in_component = Some(Box::new(reference(&self.childs[idx])));
+                }
+            }
+        }
 
-        let matched = match_all_files(&files, &provider.base_path());
-        ctx.add_techs(&matched);
+        let ctx = match ctx_idx {
+            Some(idx) => &mut self.childs[idx],
+            None => self,
+        };
+        for pl in virtuals {
+            ctx.combine_dependencies(&pl);
+            ctx.techs.extend(pl.techs);
+            ctx.reason.extend(pl.reason);
+            if ctx.in_component.is_none() {
+                ctx.in_component = pl.in_component;
+            }
+        }
+
+        let matched = match_all_files(&files, &provider.base_path(), registry);
+        ctx.add_techs(&matched, registry);
 
-        // println!("Recurse files: {:?}", &files);
         // Handle directories separately
         for file in files {
-            // println!("File {:#?}", file);
             if matches!(file.file_type, FileType::File) {
-                ctx.detect_lang(&file.name);
+                ctx.detect_lang(&file.name, registry);
                 continue;
             }
 
@@ -100,10 +140,7 @@ impl Payload {
                 continue;
             }
 
-            // ... existing directory handling code ...
-            let new_path = &file.fp;
-            // println!("Checking directory: {}", new_path); // Debug print
-            ctx.recurse(provider, new_path);
+            ctx.recurse(provider, &file.fp, registry);
         }
     }
 
@@ -117,15 +154,13 @@ impl Payload {
     }
 
     /// Helper to add a lang entry to languages.
-    pub fn add_lang(&mut self, name: &str, count: i32) {
+    pub fn add_lang(&mut self, name: &str, count: i32, registry: &RuleRegistry) {
         let entry = self.languages.entry(name.to_string()).or_insert(0);
         *entry += count;
 
-        // Using the NAME_TO_KEY from register.rs
-        let name_to_key = NAME_TO_KEY.lock().unwrap();
-        if let Some(tech_key) = name_to_key.get(name) {
+        if let Some(tech_key) = registry.name_to_key.get(name) {
             if !self.techs.contains(tech_key) {
-                self.add_tech(tech_key, &[]);
+                self.add_tech(tech_key, &[], registry);
             }
         }
     }
@@ -136,30 +171,33 @@ impl Payload {
     }
 
     /// Detect language of a file at this level.
-    pub fn detect_lang(&mut self, filename: &str) {
-        // println!("detect lang {:?} - {:?}", filename, self.id);
+    pub fn detect_lang(&mut self, filename: &str, registry: &RuleRegistry) {
         if let Some(lang) = detect_lang(filename) {
             let lang_name = lang.group.unwrap_or(lang.name);
-            self.add_lang(&lang_name, 1);
+            self.add_lang(&lang_name, 1, registry);
         }
     }
 
-    pub fn add_techs(&mut self, tech_map: &HashMap<String, Vec<String>>) {
+    pub fn add_techs(&mut self, tech_map: &HashMap<String, Vec<String>>, registry: &RuleRegistry) {
         for (tech, reasons) in tech_map {
-            self.add_tech(tech, reasons);
+            self.add_tech(tech, reasons, registry);
         }
     }
 
-    pub fn add_tech(&mut self, tech: &str, reasons: &[String]) {
+    pub fn add_tech(&mut self, tech: &str, reasons: &[String], registry: &RuleRegistry) {
         self.techs.insert(tech.to_string());
         self.reason.extend(reasons.iter().cloned());
 
-        // Note: These functions need to be implemented separately
-        find_implicit_component(self, tech, reasons);
-        find_hosting(self, tech);
+        find_implicit_component(self, tech, reasons, registry);
+        find_hosting(self, tech, registry);
     }
 
     pub fn add_child(&mut self, service: Payload) -> &mut Payload {
+        let idx = self.add_child_idx(service);
+        &mut self.childs[idx]
+    }
+
+    fn add_child_idx(&mut self, service: Payload) -> usize {
         // Find existing child with same name or tech
         let existing_idx = self.childs.iter().position(|s| {
             s.name == service.name
@@ -172,30 +210,46 @@ impl Payload {
             existing.path.extend(service.path);
             // Merge dependencies
             existing.dependencies.extend(service.dependencies);
-            existing
+            idx
         } else {
             // Add as new child
             self.childs.push(service);
-            self.childs.last_mut().unwrap()
+            self.childs.len() - 1
         }
     }
 
-    fn combine_dependencies(&mut self, other: &Payload) {
-        // Create a HashMap for deduplication using joined strings as keys
-        let mut dedup: HashMap<String, Vec<String>> = HashMap::new();
+    /// Merge another Payload describing the same component into this one.
+    /// Childs are left untouched.
+    pub fn combine(&mut self, other: &Payload) {
+        self.path.extend(other.path.iter().cloned());
+        self.techs.extend(other.techs.iter().cloned());
+        self.reason.extend(other.reason.iter().cloned());
+        for (name, count) in &other.languages {
+            *self.languages.entry(name.clone()).or_insert(0) += count;
+        }
+        self.combine_dependencies(other);
+        if self.in_component.is_none() {
+            self.in_component = other.in_component.clone();
+        }
 
-        // Add existing dependencies
-        for dep in &self.dependencies {
-            dedup.insert(dep.join("_"), dep.clone());
+        for edge in &other.edges {
+            if edge.target.id == self.id || self.edges.iter().any(|e| e.target.id == edge.target.id)
+            {
+                continue;
+            }
+            self.edges.push(edge.clone());
         }
+    }
+
+    fn combine_dependencies(&mut self, other: &Payload) {
+        // Deduplicate using joined strings as keys, keeping the original order
+        let mut seen: HashSet<String> = self.dependencies.iter().map(|dep| dep.join("_")).collect();
 
-        // Add new dependencies from other payload
         for dep in &other.dependencies {
-            dedup.insert(dep.join("_"), dep.clone());
+            if seen.insert(dep.join("_")) {
+                self.dependencies.push(dep.clone());
+            }
         }
-
-        // Update dependencies with deduplicated values
-        self.dependencies = dedup.into_values().collect();
     }
 }
 
@@ -208,13 +262,7 @@ fn generate_id() -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        provider::fake::FakeProvider,
-        rules::{
-            loader::load_all_rules,
-            register::{register_all, REGISTERED_RULES},
-        },
-    };
+    use crate::provider::fake::FakeProvider;
 
     #[test]
     fn test_recurse() {
@@ -235,18 +283,13 @@ mod tests {
 
         // println!("Available paths in provider: {:?}", paths); // Debug
 
-        register_all();
-        load_all_rules(&REGISTERED_RULES.lock().unwrap());
+        let registry = RuleRegistry::with_defaults().unwrap();
 
         let files = HashMap::new();
         let provider = FakeProvider::new(paths, files);
         let mut payload = Payload::new("test_service", "/test");
 
-        // println!("provider result: {:?}", provider);
-
-        payload.recurse(&provider, "/test");
-
-        // println!("payload result: {:?}", payload);
+        payload.recurse(&provider, "/test", &registry);
 
         // Add assertions to verify the recursion results
         assert!(
@@ -262,11 +305,6 @@ mod tests {
             "Should contain the base path"
         );
 
-        // println!("Languages detected: {:?}", payload.languages);
-        // println!("Techs detected: {:?}", payload.techs);
-        // println!("Reasons detected: {:?}", payload.reason);
-        // println!("childs detected: {:?}", payload.childs);
-        assert!(false);
         // Verify file count - we expect 2 Rust files in total
         assert_eq!(
             *payload.languages.get("Rust").unwrap_or(&0),
@@ -285,6 +323,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_recurse_into_component() {
+        let provider = FakeProvider::new(
+            HashMap::from_iter([
+                (
+                    "/".to_string(),
+                    vec!["README.md".to_string(), "web/".to_string()],
+                ),
+                (
+                    "/web/".to_string(),
+                    vec!["package.json".to_string(), "src/".to_string()],
+                ),
+                (
+                    "/web/src/".to_string(),
+                    vec!["index.ts".to_string(), "app.ts".to_string()],
+                ),
+            ]),
+            HashMap::from_iter([(
+                "/web/package.json".to_string(),
+                r#"{ "name": "web" }"#.to_string(),
+            )]),
+        );
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let mut payload = Payload::new("main", "/");
+        payload.recurse(&provider, "/", &registry);
+
+        // What is found in and below the folder of a component belongs to it, not to the root
+        assert_eq!(
+            payload.languages,
+            HashMap::from([("Markdown".to_string(), 1)])
+        );
+        assert!(payload.techs.is_empty());
+        let web = &payload.childs[0];
+        assert_eq!(web.name, "web");
+        assert_eq!(web.languages.get("TypeScript"), Some(&2));
+        assert!(web.techs.contains("typescript"));
+        assert!(web.reason.contains("matched extension: ts"));
+    }
+
+    #[test]
+    fn test_recurse_virtual_in_component() {
+        let provider = FakeProvider::new(
+            HashMap::from_iter([
+                ("/".to_string(), vec!["api/".to_string()]),
+                (
+                    "/api/".to_string(),
+                    vec!["Dockerfile".to_string(), "go.mod".to_string()],
+                ),
+            ]),
+            HashMap::from_iter([
+                (
+                    "/api/Dockerfile".to_string(),
+                    "FROM golang:1.21 AS build\nFROM gcr.io/distroless/base\n".to_string(),
+                ),
+                (
+                    "/api/go.mod".to_string(),
+                    "module github.com/acme/api\n\ngo 1.21\n".to_string(),
+                ),
+            ]),
+        );
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let mut payload = Payload::new("main", "/");
+        payload.recurse(&provider, "/", &registry);
+
+        // The docker rule runs before the golang one, base images still land in the component
+        assert_eq!(payload.childs.len(), 1);
+        let api = &payload.childs[0];
+        assert!(api.dependencies.contains(&dependency("docker", "golang", "", "1.21", "")));
+        assert!(api.techs.contains("golang"));
+        assert!(payload.dependencies.is_empty());
+    }
+
     #[test]
     fn test_add_edges() {
         let mut payload = Payload::new("service1", "/path1");
@@ -302,8 +414,9 @@ mod tests {
     fn test_add_lang() {
         let mut payload = Payload::new("service1", "/path1");
 
-        payload.add_lang("rust", 1);
-        payload.add_lang("rust", 2);
+        let registry = RuleRegistry::new();
+        payload.add_lang("rust", 1, &registry);
+        payload.add_lang("rust", 2, &registry);
 
         assert_eq!(payload.languages.len(), 1);
         assert_eq!(payload.languages.get("rust"), Some(&3));
@@ -328,13 +441,14 @@ mod tests {
     fn test_detect_lang() {
         let mut payload = Payload::new("service1", "/path1");
 
-        payload.detect_lang("main.rs");
+        let registry = RuleRegistry::new();
+        payload.detect_lang("main.rs", &registry);
         assert!(payload.languages.contains_key("Rust"));
 
-        payload.detect_lang("script.py");
+        payload.detect_lang("script.py", &registry);
         assert!(payload.languages.contains_key("Python"));
 
-        payload.detect_lang("unknown.xyz");
+        payload.detect_lang("unknown.xyz", &registry);
         assert_eq!(payload.languages.len(), 2); // Should not add unknown extensions
     }
 
@@ -345,7 +459,7 @@ mod tests {
         tech_map.insert("rust".to_string(), vec!["Cargo.toml".to_string()]);
         tech_map.insert("docker".to_string(), vec!["Dockerfile".to_string()]);
 
-        payload.add_techs(&tech_map);
+        payload.add_techs(&tech_map, &RuleRegistry::new());
 
         assert_eq!(payload.techs.len(), 2);
         assert!(payload.techs.contains("rust"));
@@ -360,7 +474,7 @@ mod tests {
         let mut payload = Payload::new("service1", "/path1");
         let reasons = vec!["package.json".to_string()];
 
-        payload.add_tech("nodejs", &reasons);
+        payload.add_tech("nodejs", &reasons, &RuleRegistry::new());
 
         assert!(payload.techs.contains("nodejs"));
         assert!(payload.reason.contains("package.json"));
@@ -391,6 +505,38 @@ mod tests {
         assert!(merged_child.path.contains("/path2"));
     }
 
+    #[test]
+    fn test_combine() {
+        let mut payload1 = Payload::new("db", "/docker-compose.yml");
+        payload1.tech = Some("postgresql".to_string());
+        payload1.techs.insert("postgresql".to_string());
+        payload1.languages.insert("SQL".to_string(), 2);
+        payload1.dependencies = vec![vec!["docker".to_string(), "postgres".to_string()]];
+
+        let mut payload2 = Payload::new("Postgres", "/api/package.json");
+        payload2.techs.insert("aws".to_string());
+        payload2.reason.insert("matched: pg".to_string());
+        payload2.languages.insert("SQL".to_string(), 1);
+        payload2.dependencies = vec![
+            vec!["docker".to_string(), "postgres".to_string()],
+            vec!["npm".to_string(), "pg".to_string()],
+        ];
+        payload2.add_edges(Payload::new("s3", "/"));
+        payload2.add_edges(payload1.clone());
+
+        payload1.combine(&payload2);
+
+        assert_eq!(payload1.name, "db");
+        assert_eq!(payload1.path.len(), 2);
+        assert_eq!(payload1.techs.len(), 2);
+        assert!(payload1.reason.contains("matched: pg"));
+        assert_eq!(payload1.languages.get("SQL"), Some(&3));
+        assert_eq!(payload1.dependencies.len(), 2);
+        // An edge to itself is dropped
+        assert_eq!(payload1.edges.len(), 1);
+        assert_eq!(payload1.edges[0].target.name, "s3");
+    }
+
     #[test]
     fn test_combine_dependencies() {
         // Create two payloads
@@ -1,3 +1,5 @@
+use std::path::{Component, Path, PathBuf};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FileType {
     Dir,
@@ -23,3 +25,142 @@ pub const IGNORED_DIVE_PATHS: &[&str] = &[
     "build",
     // ... other paths can be added as needed
 ];
+
+/// Resolve `.` and `..` without touching the filesystem, paths may not exist (e.g: FakeProvider).
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Path to go from a folder to another, e.g: `crates/api` to `crates/shared` is `../shared`
+pub fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from: Vec<Component> = from.components().collect();
+    let to: Vec<Component> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut path = PathBuf::new();
+    for _ in common..from.len() {
+        path.push("..");
+    }
+    for component in &to[common..] {
+        path.push(component);
+    }
+    path
+}
+
+/// Name of the folder containing a file, used for components that don't declare a name.
+/// A file at the root of the provider takes the name of the analysed folder, or `main`.
+pub fn folder_name(fp: &str, provider: &dyn BaseProvider) -> String {
+    let name = |path: &Path| path.file_name().map(|n| n.to_string_lossy().to_string());
+
+    Path::new(fp)
+        .parent()
+        .and_then(name)
+        .or_else(|| name(Path::new(&provider.base_path())))
+        .unwrap_or_else(|| "main".to_string())
+}
+
+/// Match a path against a glob like `crates/*` or `packages/**`,
+/// `*` and `?` never cross a `/` while `**` matches any number of folders.
+pub fn matches_glob(glob: &str, path: &Path) -> bool {
+    let patterns: Vec<&str> = glob.split('/').filter(|p| !p.is_empty() && *p != ".").collect();
+    let segments: Vec<String> = path
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+
+    matches_segments(&patterns, &segments)
+}
+
+fn matches_segments(patterns: &[&str], segments: &[String]) -> bool {
+    match (patterns.first(), segments.first()) {
+        (None, _) => segments.is_empty(),
+        (Some(&"**"), _) => {
+            matches_segments(&patterns[1..], segments)
+                || (!segments.is_empty() && matches_segments(patterns, &segments[1..]))
+        }
+        (Some(pattern), Some(segment)) => {
+            matches_segment(pattern.as_bytes(), segment.as_bytes())
+                && matches_segments(&patterns[1..], &segments[1..])
+        }
+        (Some(_), None) => false,
+    }
+}
+
+fn matches_segment(pattern: &[u8], value: &[u8]) -> bool {
+    match (pattern.first(), value.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            matches_segment(&pattern[1..], value)
+                || (!value.is_empty() && matches_segment(pattern, &value[1..]))
+        }
+        (Some(b'?'), Some(_)) => matches_segment(&pattern[1..], &value[1..]),
+        (Some(p), Some(v)) if p == v => matches_segment(&pattern[1..], &value[1..]),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{fake::FakeProvider, fs::FsProvider};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_matches_glob() {
+        assert!(matches_glob("crates/*", Path::new("crates/api")));
+        assert!(matches_glob("./crates/api-?", Path::new("crates/api-2")));
+        assert!(matches_glob(
+            "crates/*-server",
+            Path::new("crates/http-server")
+        ));
+        assert!(!matches_glob("crates/*", Path::new("crates/api/sub")));
+        assert!(!matches_glob("crates/*-server", Path::new("crates/http")));
+
+        assert!(matches_glob("packages/**", Path::new("packages/a/b")));
+        assert!(matches_glob("**/test", Path::new("packages/a/test")));
+        assert!(!matches_glob("**/test", Path::new("packages/a/tests")));
+    }
+
+    #[test]
+    fn test_folder_name() {
+        let fake = FakeProvider::new(HashMap::new(), HashMap::new());
+        assert_eq!(folder_name("/app/Gemfile", &fake), "app");
+        assert_eq!(folder_name("/Gemfile", &fake), "main");
+
+        let fs = FsProvider::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/__fixtures__/ruby"
+        ))
+        .unwrap();
+        let fp = format!("{}/Gemfile", fs.base_path());
+        assert_eq!(folder_name(&fp, &fs), "ruby");
+        assert_eq!(folder_name("Gemfile", &fs), "ruby");
+    }
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(
+            relative_path(Path::new("/crates/api"), Path::new("/crates/shared")),
+            PathBuf::from("../shared")
+        );
+        assert_eq!(
+            relative_path(Path::new("/crates/api"), Path::new("/libs/db")),
+            PathBuf::from("../../libs/db")
+        );
+        assert_eq!(
+            relative_path(Path::new("/"), Path::new("/crates/shared")),
+            PathBuf::from("crates/shared")
+        );
+    }
+}
@@ -0,0 +1,288 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::base::{BaseProvider, FileType, ProviderFile};
+
+/// Provider reading a local directory through `std::fs`.
+///
+/// Every path is resolved against the root and canonicalized before being touched,
+/// so `..` segments and symlinks can never reach a file outside of it.
+/// Symlinked directories are not listed, to avoid walking the same tree twice (or forever).
+#[derive(Debug)]
+pub struct FsProvider {
+    base_path: PathBuf,
+    max_depth: Option<usize>,
+}
+
+impl FsProvider {
+    pub fn new<T: AsRef<Path>>(base_path: T) -> io::Result<Self> {
+        let base_path = fs::canonicalize(base_path)?;
+        if !base_path.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("not a directory: {}", base_path.display()),
+            ));
+        }
+
+        Ok(Self {
+            base_path,
+            max_depth: None,
+        })
+    }
+
+    /// Stop listing directories nested deeper than `max_depth` levels below the root.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Resolve a path given by the analyser to its real location,
+    /// or None if it does not exist or lives outside of the root.
+    fn resolve(&self, path: &str) -> Option<PathBuf> {
+        let path = Path::new(path);
+        let joined = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.base_path.join(path)
+        };
+
+        let real = fs::canonicalize(joined).ok()?;
+        if !real.starts_with(&self.base_path) {
+            return None;
+        }
+
+        Some(real)
+    }
+
+    fn to_provider_file(&self, dir: &Path, entry: fs::DirEntry) -> Option<ProviderFile> {
+        // Names that are not valid UTF-8 can't round-trip through the provider API
+        let name = entry.file_name().into_string().ok()?;
+        let fp = dir.join(&name);
+
+        let entry_type = entry.file_type().ok()?;
+        let file_type = if entry_type.is_symlink() {
+            let target = self.resolve(fp.to_str()?)?;
+            if !target.is_file() {
+                return None;
+            }
+            FileType::File
+        } else if entry_type.is_dir() {
+            FileType::Dir
+        } else {
+            FileType::File
+        };
+
+        Some(ProviderFile {
+            name,
+            file_type,
+            fp: fp.to_string_lossy().to_string(),
+        })
+    }
+}
+
+impl BaseProvider for FsProvider {
+    fn base_path(&self) -> String {
+        self.base_path.to_string_lossy().to_string()
+    }
+
+    fn list_dir(&self, path: &str) -> Vec<ProviderFile> {
+        let Some(dir) = self.resolve(path) else {
+            return vec![];
+        };
+
+        if let Some(max_depth) = self.max_depth {
+            let depth = dir.strip_prefix(&self.base_path).map_or(0, |p| p.components().count());
+            if depth > max_depth {
+                return vec![];
+            }
+        }
+
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("Failed to list directory: {} - {}", dir.display(), e);
+                return vec![];
+            }
+        };
+
+        let mut result = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| self.to_provider_file(&dir, entry))
+            .collect::<Vec<_>>();
+        result.sort_by(|a, b| a.name.cmp(&b.name));
+        result
+    }
+
+    fn open(&self, path: &str) -> Option<String> {
+        let real = self.resolve(path)?;
+        if !real.is_file() {
+            return None;
+        }
+
+        let bytes = match fs::read(&real) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                log::warn!("Failed to read file: {} - {}", real.display(), e);
+                return None;
+            }
+        };
+
+        // Manifests are expected to be text, a stray invalid byte should not hide the whole file
+        match String::from_utf8(bytes) {
+            Ok(content) => Some(content),
+            Err(e) => Some(String::from_utf8_lossy(e.as_bytes()).to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Temporary directory removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let path =
+                std::env::temp_dir().join(format!("a1-stack-analyser-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(&path).unwrap();
+            Self(fs::canonicalize(path).unwrap())
+        }
+
+        fn write(&self, relative: &str, content: &[u8]) {
+            let fp = self.0.join(relative);
+            fs::create_dir_all(fp.parent().unwrap()).unwrap();
+            fs::write(fp, content).unwrap();
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn setup_test_dir() -> TempDir {
+        let dir = TempDir::new();
+        dir.write("file1.txt", b"content1");
+        dir.write("dir1/file2.txt", b"content2");
+        fs::create_dir_all(dir.0.join("dir1/subdir")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_new() {
+        let dir = setup_test_dir();
+        assert!(FsProvider::new(&dir.0).is_ok());
+        assert!(FsProvider::new(dir.0.join("file1.txt")).is_err());
+        assert!(FsProvider::new(dir.0.join("nonexistent")).is_err());
+    }
+
+    #[test]
+    fn test_base_path() {
+        let dir = setup_test_dir();
+        let provider = FsProvider::new(&dir.0).unwrap();
+        assert_eq!(provider.base_path(), dir.0.to_string_lossy());
+    }
+
+    #[test]
+    fn test_list_dir() {
+        let dir = setup_test_dir();
+        let provider = FsProvider::new(&dir.0).unwrap();
+
+        // Test root directory
+        let root_files = provider.list_dir(&provider.base_path());
+        assert_eq!(root_files.len(), 2);
+        assert_eq!(root_files[0].name, "dir1");
+        assert_eq!(root_files[0].file_type, FileType::Dir);
+        assert_eq!(root_files[0].fp, dir.0.join("dir1").to_string_lossy());
+        assert_eq!(root_files[1].name, "file1.txt");
+        assert_eq!(root_files[1].file_type, FileType::File);
+
+        // Test subdirectory, with an absolute and a relative path
+        let dir1_files = provider.list_dir(&root_files[0].fp);
+        assert_eq!(dir1_files.len(), 2);
+        assert_eq!(dir1_files[0].name, "file2.txt");
+        assert_eq!(dir1_files[0].file_type, FileType::File);
+        assert_eq!(dir1_files[1].name, "subdir");
+        assert_eq!(dir1_files[1].file_type, FileType::Dir);
+        assert_eq!(provider.list_dir("dir1").len(), 2);
+
+        // Test non-existent directory
+        assert!(provider.list_dir("nonexistent").is_empty());
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let dir = setup_test_dir();
+        let provider = FsProvider::new(&dir.0).unwrap().with_max_depth(1);
+
+        assert_eq!(provider.list_dir(&provider.base_path()).len(), 2);
+        assert_eq!(provider.list_dir("dir1").len(), 2);
+        dir.write("dir1/subdir/file3.txt", b"content3");
+        assert!(provider.list_dir("dir1/subdir").is_empty());
+    }
+
+    #[test]
+    fn test_open() {
+        let dir = setup_test_dir();
+        dir.write("binary.bin", &[b'a', 0xff, b'b']);
+        let provider = FsProvider::new(&dir.0).unwrap();
+
+        // Test existing files
+        assert_eq!(provider.open("file1.txt"), Some("content1".to_string()));
+        assert_eq!(
+            provider.open(&dir.0.join("dir1/file2.txt").to_string_lossy()),
+            Some("content2".to_string())
+        );
+
+        // Invalid UTF-8 is replaced instead of failing
+        assert_eq!(provider.open("binary.bin"), Some("a\u{FFFD}b".to_string()));
+
+        // Test non-existent file and directory
+        assert_eq!(provider.open("nonexistent.txt"), None);
+        assert_eq!(provider.open("dir1"), None);
+    }
+
+    #[test]
+    fn test_should_not_escape_root() {
+        let outside = setup_test_dir();
+        let dir = TempDir::new();
+        dir.write("inside/file.txt", b"inside");
+        let provider = FsProvider::new(dir.0.join("inside")).unwrap();
+
+        assert!(provider.list_dir("..").is_empty());
+        assert!(provider.list_dir(&outside.0.to_string_lossy()).is_empty());
+        assert_eq!(
+            provider.open("../inside/file.txt"),
+            Some("inside".to_string())
+        );
+        assert_eq!(
+            provider.open(&format!("{}/file1.txt", outside.0.to_string_lossy())),
+            None
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_should_not_follow_symlinks_outside_root() {
+        let outside = setup_test_dir();
+        let dir = TempDir::new();
+        dir.write("file.txt", b"inside");
+        std::os::unix::fs::symlink(outside.0.join("file1.txt"), dir.0.join("escape.txt")).unwrap();
+        std::os::unix::fs::symlink(outside.0.join("dir1"), dir.0.join("escape")).unwrap();
+        std::os::unix::fs::symlink(dir.0.join("file.txt"), dir.0.join("link.txt")).unwrap();
+        std::os::unix::fs::symlink(&dir.0, dir.0.join("loop")).unwrap();
+        let provider = FsProvider::new(&dir.0).unwrap();
+
+        let names: Vec<String> =
+            provider.list_dir(&provider.base_path()).into_iter().map(|f| f.name).collect();
+        assert_eq!(names, vec!["file.txt", "link.txt"]);
+
+        assert_eq!(provider.open("link.txt"), Some("inside".to_string()));
+        assert_eq!(provider.open("escape.txt"), None);
+        assert!(provider.list_dir("escape").is_empty());
+    }
+}
@@ -0,0 +1,22 @@
+use regex::Regex;
+
+use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
+use crate::types::rule::{Rule, RuleFiles};
+
+pub fn register_circleci(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("circleci"),
+            name: String::from("CircleCI"),
+            r#type: String::from("ci"),
+            files: Some(RuleFiles::MatchFullPath {
+                match_full_path: true,
+                files: Regex::new(r"^\.circleci/config\.ya?ml$").unwrap(),
+                example: String::from(".circleci/config.yml"),
+            }),
+            ..Default::default()
+        },
+    )
+}
@@ -0,0 +1,61 @@
+use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
+use crate::types::rule::{Rule, RuleDependency};
+
+pub fn register_aws(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("aws"),
+            name: String::from("AWS"),
+            r#type: String::from("cloud"),
+            dependencies: Some(vec![RuleDependency {
+                r#type: String::from("terraform"),
+                name: Some(String::from("registry.terraform.io/hashicorp/aws")),
+                example: Some(String::from("registry.terraform.io/hashicorp/aws")),
+            }]),
+            ..Default::default()
+        },
+    )
+}
+
+pub fn register_aws_rds(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("aws.rds"),
+            name: String::from("AWS RDS"),
+            r#type: String::from("db"),
+            dependencies: Some(vec![
+                RuleDependency {
+                    r#type: String::from("terraform.resource"),
+                    name: Some(String::from("aws_db_instance")),
+                    example: Some(String::from("aws_db_instance")),
+                },
+                RuleDependency {
+                    r#type: String::from("terraform.resource"),
+                    name: Some(String::from("aws_rds_cluster")),
+                    example: Some(String::from("aws_rds_cluster")),
+                },
+            ]),
+            ..Default::default()
+        },
+    )
+}
+
+pub fn register_aws_s3(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("aws.s3"),
+            name: String::from("AWS S3"),
+            r#type: String::from("storage"),
+            dependencies: Some(vec![RuleDependency {
+                r#type: String::from("terraform.resource"),
+                name: Some(String::from("aws_s3_bucket")),
+                example: Some(String::from("aws_s3_bucket")),
+            }]),
+            ..Default::default()
+        },
+    )
+}
@@ -0,0 +1,81 @@
+use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
+use crate::types::rule::{Rule, RuleDependency, RuleFiles};
+
+pub fn register_gcp(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("gcp"),
+            name: String::from("Google Cloud Platform"),
+            r#type: String::from("cloud"),
+            dependencies: Some(vec![
+                RuleDependency {
+                    r#type: String::from("terraform"),
+                    name: Some(String::from("registry.terraform.io/hashicorp/google")),
+                    example: Some(String::from("registry.terraform.io/hashicorp/google")),
+                },
+                RuleDependency {
+                    r#type: String::from("terraform"),
+                    name: Some(String::from("registry.terraform.io/hashicorp/google-beta")),
+                    example: Some(String::from("registry.terraform.io/hashicorp/google-beta")),
+                },
+            ]),
+            ..Default::default()
+        },
+    )
+}
+
+pub fn register_gcp_sql(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("gcp.sql"),
+            name: String::from("GCP Cloud SQL"),
+            r#type: String::from("db"),
+            dependencies: Some(vec![RuleDependency {
+                r#type: String::from("terraform.resource"),
+                name: Some(String::from("google_sql_database_instance")),
+                example: Some(String::from("google_sql_database_instance")),
+            }]),
+            ..Default::default()
+        },
+    )
+}
+
+pub fn register_gcp_gcs(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("gcp.gcs"),
+            name: String::from("GCP Cloud Storage"),
+            r#type: String::from("storage"),
+            dependencies: Some(vec![RuleDependency {
+                r#type: String::from("terraform.resource"),
+                name: Some(String::from("google_storage_bucket")),
+                example: Some(String::from("google_storage_bucket")),
+            }]),
+            ..Default::default()
+        },
+    )
+}
+
+pub fn register_gcp_appengine(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("gcp.appengine"),
+            name: String::from("GCP App Engine"),
+            r#type: String::from("hosting"),
+            files: Some(RuleFiles::FilesArray {
+                files: vec![String::from("app.yaml")],
+            }),
+            dependencies: Some(vec![RuleDependency {
+                r#type: String::from("terraform.resource"),
+                name: Some(String::from("google_app_engine_application")),
+                example: Some(String::from("google_app_engine_application")),
+            }]),
+            ..Default::default()
+        },
+    )
+}
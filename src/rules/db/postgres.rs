@@ -1,93 +1,117 @@
 use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
 use crate::types::rule::{Rule, RuleDependency};
 
-pub fn register_postgres() -> Result<(), String> {
-    register(Rule {
-        tech: String::from("postgresql"),
-        name: String::from("Postgres"),
-        r#type: String::from("db"),
-        dependencies: Some(vec![
-            RuleDependency {
-                r#type: String::from("npm"),
-                name: Some(String::from("pg")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("npm"),
-                name: Some(String::from("postgres")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("npm"),
-                name: Some(String::from("postgres-interval")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("npm"),
-                name: Some(String::from("@opentelemetry/instrumentation-pg")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("npm"),
-                name: Some(String::from("@mikro-orm/postgresql")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("npm"),
-                name: Some(String::from("pg-connection-string")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("docker"),
-                name: Some(String::from("postgres")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("docker"),
-                name: Some(String::from("bitnami/postgresql")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("docker"),
-                name: Some(String::from("circleci/postgres")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("docker"),
-                name: Some(String::from("cimg/postgres")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("docker"),
-                name: Some(String::from("ubuntu/postgres")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("docker"),
-                name: Some(String::from("bitnamicharts/postgresql")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("rust"),
-                name: Some(String::from("postgres")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("ruby"),
-                name: Some(String::from("pg")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("deno"),
-                name: Some(String::from("/x/postgres@/")),
-                example: Some(String::from("https://deno.land/x/postgres@v0.17.0/mod.ts")),
-            },
-            RuleDependency {
-                r#type: String::from("php"),
-                name: Some(String::from("martin-georgiev/postgresql-for-doctrine")),
-                ..Default::default()
-            },
-        ]),
-        ..Default::default()
-    })
+pub fn register_postgres(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("postgresql"),
+            name: String::from("Postgres"),
+            r#type: String::from("db"),
+            dependencies: Some(vec![
+                RuleDependency {
+                    r#type: String::from("npm"),
+                    name: Some(String::from("pg")),
+                    example: Some(String::from("pg")),
+                },
+                RuleDependency {
+                    r#type: String::from("npm"),
+                    name: Some(String::from("postgres")),
+                    example: Some(String::from("postgres")),
+                },
+                RuleDependency {
+                    r#type: String::from("npm"),
+                    name: Some(String::from("postgres-interval")),
+                    example: Some(String::from("postgres-interval")),
+                },
+                RuleDependency {
+                    r#type: String::from("npm"),
+                    name: Some(String::from("@opentelemetry/instrumentation-pg")),
+                    example: Some(String::from("@opentelemetry/instrumentation-pg")),
+                },
+                RuleDependency {
+                    r#type: String::from("npm"),
+                    name: Some(String::from("@mikro-orm/postgresql")),
+                    example: Some(String::from("@mikro-orm/postgresql")),
+                },
+                RuleDependency {
+                    r#type: String::from("npm"),
+                    name: Some(String::from("pg-connection-string")),
+                    example: Some(String::from("pg-connection-string")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("postgres")),
+                    example: Some(String::from("postgres:16-alpine")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("bitnami/postgresql")),
+                    example: Some(String::from("bitnami/postgresql:16")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("circleci/postgres")),
+                    example: Some(String::from("circleci/postgres:12.4")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("cimg/postgres")),
+                    example: Some(String::from("cimg/postgres:15.4")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("ubuntu/postgres")),
+                    example: Some(String::from("ubuntu/postgres:14-22.04_beta")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("bitnamicharts/postgresql")),
+                    example: Some(String::from("bitnamicharts/postgresql:13.2.24")),
+                },
+                RuleDependency {
+                    r#type: String::from("rust"),
+                    name: Some(String::from("postgres")),
+                    example: Some(String::from("postgres")),
+                },
+                RuleDependency {
+                    r#type: String::from("python"),
+                    name: Some(String::from("psycopg2")),
+                    example: Some(String::from("psycopg2")),
+                },
+                RuleDependency {
+                    r#type: String::from("python"),
+                    name: Some(String::from("psycopg2-binary")),
+                    example: Some(String::from("psycopg2-binary")),
+                },
+                RuleDependency {
+                    r#type: String::from("python"),
+                    name: Some(String::from("psycopg")),
+                    example: Some(String::from("psycopg")),
+                },
+                RuleDependency {
+                    r#type: String::from("python"),
+                    name: Some(String::from("asyncpg")),
+                    example: Some(String::from("asyncpg")),
+                },
+                RuleDependency {
+                    r#type: String::from("ruby"),
+                    name: Some(String::from("pg")),
+                    example: Some(String::from("pg")),
+                },
+                RuleDependency {
+                    r#type: String::from("deno"),
+                    name: Some(String::from("/x/postgres@/")),
+                    example: Some(String::from("https://deno.land/x/postgres@v0.17.0/mod.ts")),
+                },
+                RuleDependency {
+                    r#type: String::from("php"),
+                    name: Some(String::from("martin-georgiev/postgresql-for-doctrine")),
+                    example: Some(String::from("martin-georgiev/postgresql-for-doctrine")),
+                },
+            ]),
+            ..Default::default()
+        },
+    )
 }
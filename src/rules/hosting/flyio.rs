@@ -0,0 +1,18 @@
+use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
+use crate::types::rule::{Rule, RuleFiles};
+
+pub fn register_flyio(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("flyio"),
+            name: String::from("Fly.io"),
+            r#type: String::from("hosting"),
+            files: Some(RuleFiles::FilesArray {
+                files: vec![String::from("fly.toml")],
+            }),
+            ..Default::default()
+        },
+    )
+}
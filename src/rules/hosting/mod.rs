@@ -0,0 +1,3 @@
+pub mod flyio;
+pub mod netlify;
+pub mod vercel;
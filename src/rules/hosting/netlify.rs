@@ -0,0 +1,23 @@
+use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
+use crate::types::rule::{Rule, RuleDependency, RuleFiles};
+
+pub fn register_netlify(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("netlify"),
+            name: String::from("Netlify"),
+            r#type: String::from("hosting"),
+            files: Some(RuleFiles::FilesArray {
+                files: vec![String::from(".netlify/"), String::from("netlify.toml")],
+            }),
+            dependencies: Some(vec![RuleDependency {
+                r#type: String::from("terraform"),
+                name: Some(String::from("registry.terraform.io/netlify/netlify")),
+                example: Some(String::from("registry.terraform.io/netlify/netlify")),
+            }]),
+            ..Default::default()
+        },
+    )
+}
@@ -0,0 +1,23 @@
+use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
+use crate::types::rule::{Rule, RuleDependency, RuleFiles};
+
+pub fn register_vercel(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("vercel"),
+            name: String::from("Vercel"),
+            r#type: String::from("hosting"),
+            files: Some(RuleFiles::FilesArray {
+                files: vec![String::from(".vercel/"), String::from("vercel.json")],
+            }),
+            dependencies: Some(vec![RuleDependency {
+                r#type: String::from("terraform"),
+                name: Some(String::from("registry.terraform.io/vercel/vercel")),
+                example: Some(String::from("registry.terraform.io/vercel/vercel")),
+            }]),
+            ..Default::default()
+        },
+    )
+}
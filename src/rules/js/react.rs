@@ -1,17 +1,21 @@
 use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
 use crate::types::rule::{Rule, RuleDependency};
 
-pub fn register_react() -> Result<(), String> {
-    register(Rule {
-        tech: String::from("react"),
-        name: String::from("React"),
-        r#type: String::from("language"),
-        dependencies: Some(vec![RuleDependency {
-            r#type: String::from("npm"),
-            name: Some(String::from("react")),
+pub fn register_react(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("react"),
+            name: String::from("React"),
+            r#type: String::from("language"),
+            dependencies: Some(vec![RuleDependency {
+                r#type: String::from("npm"),
+                name: Some(String::from("react")),
+                example: Some(String::from("react")),
+            }]),
+            extensions: Some(vec![String::from("tsx"), String::from("jsx")]),
             ..Default::default()
-        }]),
-        extensions: Some(vec![String::from("tsx"), String::from("jsx")]),
-        ..Default::default()
-    })
+        },
+    )
 }
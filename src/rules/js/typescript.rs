@@ -1,19 +1,23 @@
 use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
 use crate::types::rule::{Rule, RuleDependency};
 
-pub fn register_typescript() -> Result<(), String> {
-    register(Rule {
-        tech: String::from("typescript"),
-        name: String::from("Typescript"),
-        r#type: String::from("language"),
-        dependencies: Some(vec![RuleDependency {
-            r#type: String::from("npm"),
-            name: Some(String::from("typescript")),
-            ..Default::default()
-        }]),
+pub fn register_typescript(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("typescript"),
+            name: String::from("Typescript"),
+            r#type: String::from("language"),
+            dependencies: Some(vec![RuleDependency {
+                r#type: String::from("npm"),
+                name: Some(String::from("typescript")),
+                example: Some(String::from("typescript")),
+            }]),
 
-        extensions: Some(vec![String::from("ts"), String::from("tsx")]),
-        example: Some(String::from("tsconfig.json")),
-        ..Default::default()
-    })
+            extensions: Some(vec![String::from("ts"), String::from("tsx")]),
+            example: Some(String::from("tsconfig.json")),
+            ..Default::default()
+        },
+    )
 }
@@ -1,38 +1,13 @@
-use lazy_static::lazy_static;
 use regex::Regex;
+use serde_json::Value;
 use std::collections::{HashMap, HashSet};
-use std::sync::Mutex;
+use std::fs;
+use std::path::Path;
 
 use crate::rules::match_files::{match_extensions, match_files, match_files_regex};
-use crate::rules::register::REGISTERED_RULES;
-use crate::types::rule::{
-    ComponentMatcher, ExtensionMatcher, Rule, RuleDependency, RuleFiles, TechMatcher,
-};
-
-lazy_static! {
-    pub static ref RULES_TECHS: Mutex<Vec<TechMatcher>> = Mutex::new(Vec::new());
-    pub static ref RULES_EXTENSIONS: Mutex<Vec<ExtensionMatcher>> = Mutex::new(Vec::new());
-    pub static ref RULES_COMPONENTS: Mutex<Vec<ComponentMatcher>> = Mutex::new(Vec::new());
-
-    pub static ref DEPENDENCIES: Mutex<HashMap<String, Vec<DependencyMatcher>>> = Mutex::new({
-        let mut m = HashMap::new();
-        // Initialize with empty vectors for each supported dependency type
-        m.insert("terraform.resource".to_string(), Vec::new());
-        m.insert("deno".to_string(), Vec::new());
-        m.insert("docker".to_string(), Vec::new());
-        m.insert("golang".to_string(), Vec::new());
-        m.insert("npm".to_string(), Vec::new());
-        m.insert("php".to_string(), Vec::new());
-        m.insert("python".to_string(), Vec::new());
-        m.insert("ruby".to_string(), Vec::new());
-        m.insert("rust".to_string(), Vec::new());
-        m.insert("terraform".to_string(), Vec::new());
-        m.insert("githubAction".to_string(), Vec::new());
-        m
-    });
-
-    pub static ref RAW_LIST: Mutex<Vec<RuleEntry>> = Mutex::new(Vec::new());
-}
+use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
+use crate::types::rule::{DependencyKind, Rule, RuleDependency, RuleFiles};
 
 #[derive(Debug)]
 pub struct DependencyMatcher {
@@ -40,41 +15,98 @@ pub struct DependencyMatcher {
     pub tech: String,
 }
 
+/// Matchers of one dependency type.
+#[derive(Debug, Default)]
+pub struct DependencyMatchers {
+    /// Exact names and the techs they point to, looked up directly
+    pub exact: HashMap<String, Vec<String>>,
+    /// Globs and regexes, tried one by one
+    pub patterns: Vec<DependencyMatcher>,
+}
+
+impl DependencyMatchers {
+    pub fn len(&self) -> usize {
+        self.exact.values().map(|techs| techs.len()).sum::<usize>() + self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Techs pointed to by a dependency
+    pub fn find<'a>(&'a self, dependency: &'a str) -> impl Iterator<Item = &'a String> + 'a {
+        let exact = self.exact.get(dependency).into_iter().flatten();
+        let patterns = self
+            .patterns
+            .iter()
+            .filter(move |matcher| matcher.match_pattern.is_match(dependency))
+            .map(|matcher| &matcher.tech);
+        exact.chain(patterns)
+    }
+}
+
 pub enum RuleEntry {
     Dependency { ref_rule: RuleDependency },
     Extension { ref_rule: Rule },
     File { ref_rule: Rule },
 }
 
-pub fn load_all_rules(registered_rules: &[Rule]) {
+pub fn load_all_rules(registry: &mut RuleRegistry) {
+    let registered_rules = registry.registered_rules.clone();
     for rule in registered_rules.iter() {
-        load_one(&rule);
+        load_one(registry, rule);
+    }
+}
+
+/// Build the pattern used to match a dependency name, whatever its `DependencyKind`.
+///
+/// A `/regex/` is searched anywhere in the dependency, which is how URLs (deno imports) are
+/// matched. Exact names and globs must match the whole dependency.
+pub fn dependency_pattern(name: &str) -> Result<Regex, regex::Error> {
+    match DependencyKind::of(name) {
+        DependencyKind::Regex => Regex::new(&name[1..name.len() - 1]),
+        DependencyKind::Glob => {
+            let parts: Vec<String> = name.split('*').map(regex::escape).collect();
+            Regex::new(&format!("^{}$", parts.join(".*")))
+        }
+        DependencyKind::Exact => Regex::new(&format!("^{}$", regex::escape(name))),
     }
-    // println!("Loaded {} rules", registered_rules.len());
 }
 
-pub fn load_one(rule: &Rule) {
+pub fn load_one(registry: &mut RuleRegistry, rule: &Rule) {
     // Handle dependencies
     if let Some(deps) = &rule.dependencies {
         for dep in deps {
             if let Some(name) = &dep.name {
+                // Registered rules are validated, this only protects rules loaded directly
                 if name.is_empty() {
-                    panic!(
-                        "empty dependency name for {} ({} > {})",
-                        rule.name, rule.r#type, rule.tech
-                    );
+                    log::warn!("Empty dependency name for {}", rule.tech);
+                    continue;
                 }
-
-                let pattern = Regex::new(&format!("^{}$", name)).unwrap();
-                let mut dependencies = DEPENDENCIES.lock().unwrap();
-                if let Some(dep_list) = dependencies.get_mut(&dep.r#type) {
-                    dep_list.push(DependencyMatcher {
-                        match_pattern: pattern,
-                        tech: rule.tech.clone(),
-                    });
+                let Some(matchers) = registry.dependencies.get_mut(&dep.r#type) else {
+                    continue;
+                };
+                if DependencyKind::of(name) == DependencyKind::Exact {
+                    matchers.exact.entry(name.clone()).or_default().push(rule.tech.clone());
+                } else {
+                    match dependency_pattern(name) {
+                        Ok(pattern) => matchers.patterns.push(DependencyMatcher {
+                            match_pattern: pattern,
+                            tech: rule.tech.clone(),
+                        }),
+                        Err(e) => {
+                            log::warn!(
+                                "Invalid dependency name for {}: {} - {}",
+                                rule.tech,
+                                name,
+                                e
+                            );
+                            continue;
+                        }
+                    }
                 }
 
-                RAW_LIST.lock().unwrap().push(RuleEntry::Dependency {
+                registry.raw_list.push(RuleEntry::Dependency {
                     ref_rule: dep.clone(),
                 });
             }
@@ -84,44 +116,215 @@ pub fn load_one(rule: &Rule) {
     // Handle file matchers
     match &rule.files {
         Some(RuleFiles::FilesArray { files }) => {
-            let tech = rule.tech.clone();
+            let ref_rule = rule.clone();
             let files_clone = files.clone();
-            RULES_TECHS.lock().unwrap().push(Box::new(move |file_list| {
-                match_files(&tech, &file_list, &files_clone, false).ok_or(false)
+            registry.rules_techs.push(Box::new(move |file_list| {
+                match_files(&ref_rule, &file_list, &files_clone, false).ok_or(false)
             }));
-            RAW_LIST.lock().unwrap().push(RuleEntry::File {
+            registry.raw_list.push(RuleEntry::File {
                 ref_rule: rule.clone(),
             });
         }
         Some(RuleFiles::FilesRegex { files, .. }) => {
-            let tech = rule.tech.clone();
+            let ref_rule = rule.clone();
             let pattern = files.clone();
-            RULES_TECHS.lock().unwrap().push(Box::new(move |file_list| {
-                match_files_regex(&tech, &file_list, &pattern, false).ok_or(false)
+            registry.rules_techs.push(Box::new(move |file_list| {
+                match_files_regex(&ref_rule, &file_list, &pattern, false).ok_or(false)
             }));
-            RAW_LIST.lock().unwrap().push(RuleEntry::File {
+            registry.raw_list.push(RuleEntry::File {
                 ref_rule: rule.clone(),
             });
         }
-        _ => {}
+        // Paths are relative to the root of the analysis, e.g: `.github/workflows/ci.yml`
+        Some(RuleFiles::MatchFullPath {
+            match_full_path,
+            files,
+            ..
+        }) => {
+            let ref_rule = rule.clone();
+            let pattern = files.clone();
+            let match_full_path = *match_full_path;
+            registry.rules_techs.push(Box::new(move |file_list| {
+                match_files_regex(&ref_rule, &file_list, &pattern, match_full_path).ok_or(false)
+            }));
+            registry.raw_list.push(RuleEntry::File {
+                ref_rule: rule.clone(),
+            });
+        }
+        Some(RuleFiles::NoFiles) | None => {}
     }
 
     // Handle extensions
     if let Some(extensions) = &rule.extensions {
-        let tech = rule.tech.clone();
+        let ref_rule = rule.clone();
         let exts: HashSet<String> = extensions.iter().cloned().collect();
-        RULES_EXTENSIONS.lock().unwrap().push(Box::new(move |list| {
-            match_extensions(&tech, &list, &exts).ok_or(false)
+        registry.rules_extensions.push(Box::new(move |list| {
+            match_extensions(&ref_rule, &list, &exts).ok_or(false)
         }));
-        RAW_LIST.lock().unwrap().push(RuleEntry::Extension {
+        registry.raw_list.push(RuleEntry::Extension {
             ref_rule: rule.clone(),
         });
     }
 
     // Handle component detectors
     if let Some(detectors) = &rule.detect {
-        RULES_COMPONENTS.lock().unwrap().extend(detectors.iter().cloned());
+        registry.rules_components.extend(detectors.iter().cloned());
+    }
+}
+
+/// Formats a rule file can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl RuleFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "yaml" | "yml" => Some(RuleFormat::Yaml),
+            "json" => Some(RuleFormat::Json),
+            "toml" => Some(RuleFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// Read the rules of a data file: a single rule, a list of rules, or `rules: [...]`
+/// (the only form possible in TOML, with `[[rules]]`).
+///
+/// ```yaml
+/// tech: mycorp
+/// name: MyCorp SDK
+/// type: saas
+/// files: ["mycorp.json"]
+/// dependencies:
+///   - { type: npm, name: "@mycorp/sdk" }
+///   - { type: docker, name: registry.mycorp.io/api }
+/// ```
+pub fn parse_rules(content: &str, format: RuleFormat) -> Result<Vec<Rule>, String> {
+    parse_rule_file(content, format).map(|(_, rules)| rules)
+}
+
+/// Tech keys declared next to `rules: [...]` with `custom_keys`, and the rules.
+fn parse_rule_file(content: &str, format: RuleFormat) -> Result<(Vec<String>, Vec<Rule>), String> {
+    let value: Value = match format {
+        RuleFormat::Yaml => {
+            let yaml: serde_yaml::Value =
+                serde_yaml::from_str(content).map_err(|e| e.to_string())?;
+            serde_json::to_value(yaml).map_err(|e| e.to_string())?
+        }
+        RuleFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string())?,
+        RuleFormat::Toml => {
+            let toml: toml::Value = toml::from_str(content).map_err(|e| e.to_string())?;
+            serde_json::to_value(toml).map_err(|e| e.to_string())?
+        }
+    };
+
+    let mut custom_keys = Vec::new();
+    let list = match value {
+        Value::Array(list) => list,
+        Value::Object(mut map) if map.contains_key("rules") => {
+            if let Some(key) = map.keys().find(|key| *key != "rules" && *key != "custom_keys") {
+                return Err(format!(
+                    "unknown field `{}`, expected `rules` or `custom_keys`",
+                    key
+                ));
+            }
+            if let Some(keys) = map.remove("custom_keys") {
+                custom_keys = serde_json::from_value(keys)
+                    .map_err(|_| "custom_keys must be a list of strings".to_string())?;
+            }
+            match map.remove("rules") {
+                Some(Value::Array(list)) => list,
+                _ => return Err("rules must be a list".to_string()),
+            }
+        }
+        Value::Object(map) => vec![Value::Object(map)],
+        _ => return Err("expected a rule or a list of rules".to_string()),
+    };
+
+    let rules = list
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let tech = value["tech"].as_str().unwrap_or("?").to_string();
+            serde_json::from_value(value).map_err(|e| format!("rule #{} ({}): {}", i, tech, e))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((custom_keys, rules))
+}
+
+/// Register and load the rules of a data file, on top of the ones already in the registry.
+/// Returns how many rules were added.
+///
+/// Techs that are not in `ALLOWED_KEYS` must be declared by the file, e.g: in-house services:
+///
+/// ```yaml
+/// custom_keys: [mycorp]
+/// rules:
+///   - { tech: mycorp, name: MyCorp SDK, type: saas }
+/// ```
+pub fn load_rules_from_str(
+    registry: &mut RuleRegistry,
+    content: &str,
+    format: RuleFormat,
+) -> Result<usize, String> {
+    let (custom_keys, rules) = parse_rule_file(content, format)?;
+
+    // The whole file is checked first, on a copy: nothing is registered if one rule is invalid
+    let mut staging = RuleRegistry::new()
+        .with_custom_keys(registry.custom_keys.iter().chain(&custom_keys).cloned());
+    staging.registered_rules = registry.registered_rules.clone();
+    staging.list_indexed = registry.list_indexed.clone();
+    // Report every rule that failed, not only the first one
+    let errors: Vec<String> = rules
+        .iter()
+        .filter_map(|rule| register(&mut staging, rule.clone()).err())
+        .collect();
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    registry.custom_keys.extend(custom_keys);
+    for rule in &rules {
+        register(registry, rule.clone())?;
+    }
+    for rule in &rules {
+        load_one(registry, rule);
+    }
+    Ok(rules.len())
+}
+
+/// Load every `.yaml`, `.yml`, `.json` and `.toml` file of a folder, by name.
+/// Returns how many rules were added.
+pub fn load_rules_from_dir(registry: &mut RuleRegistry, dir: &Path) -> Result<usize, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && RuleFormat::from_path(path).is_some())
+        .collect();
+    paths.sort();
+
+    let mut count = 0;
+    let mut errors = Vec::new();
+    for path in paths {
+        let format = RuleFormat::from_path(&path).unwrap_or(RuleFormat::Yaml);
+        let loaded = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| load_rules_from_str(registry, &content, format));
+        match loaded {
+            Ok(loaded) => count += loaded,
+            Err(e) => errors.extend(e.lines().map(|line| format!("{}: {}", path.display(), line))),
+        }
     }
+
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+    Ok(count)
 }
 
 #[cfg(test)]
@@ -129,33 +332,13 @@ mod tests {
     use super::*;
     use crate::payload::payload::Payload;
     use crate::provider::base::{BaseProvider, FileType, ProviderFile};
-    use crate::rules::register::{self};
+    use crate::rules::register;
+    use crate::types::rule::ComponentMatcher;
     use std::collections::HashSet;
-    use std::sync::Once;
-
-    static INIT: Once = Once::new();
-
-    fn initialize() {
-        INIT.call_once(|| {
-            // Currently empty as lazy_static! handles our initialization
-            // Keep this function if you anticipate needing test-specific setup in the future
-        });
-    }
-
-    fn clear_test_storage() {
-        DEPENDENCIES.lock().unwrap().iter_mut().for_each(|(_, v)| v.clear());
-        RULES_TECHS.lock().unwrap().clear();
-        RULES_EXTENSIONS.lock().unwrap().clear();
-        RULES_COMPONENTS.lock().unwrap().clear();
-        RAW_LIST.lock().unwrap().clear();
-        REGISTERED_RULES.lock().unwrap().clear();
-    }
 
     #[test]
-    #[serial_test::serial]
     fn test_load_one_with_dependency() {
-        initialize();
-        clear_test_storage();
+        let mut registry = RuleRegistry::new();
 
         let rule = Rule {
             name: "test-rule".to_string(),
@@ -169,23 +352,60 @@ mod tests {
             ..Default::default()
         };
 
-        register::register(rule.clone()).unwrap();
+        register::register(&mut registry, rule.clone()).unwrap();
 
-        load_one(&rule);
+        load_one(&mut registry, &rule);
 
         // Verify dependency was added
-        let deps = DEPENDENCIES.lock().unwrap();
-        let python_deps = deps.get("python").unwrap();
+        let python_deps = registry.dependencies.get("python").unwrap();
         assert_eq!(python_deps.len(), 1);
-        assert_eq!(python_deps[0].tech, "python");
-        assert!(python_deps[0].match_pattern.is_match("django"));
+        assert_eq!(python_deps.exact["django"], vec!["python"]);
+        assert_eq!(
+            python_deps.find("django").collect::<Vec<_>>(),
+            vec!["python"]
+        );
+        assert!(python_deps.find("django-rest").next().is_none());
+    }
+
+    #[test]
+    fn test_dependency_pattern() {
+        let exact = dependency_pattern("pg").unwrap();
+        assert!(exact.is_match("pg"));
+        assert!(!exact.is_match("pg-pool"));
+        let exact = dependency_pattern("@opentelemetry/instrumentation-pg").unwrap();
+        assert!(!exact.is_match("@opentelemetry/instrumentation_pg"));
+        assert!(dependency_pattern("c++").is_ok());
+
+        let glob = dependency_pattern("@aws-sdk/*").unwrap();
+        assert!(glob.is_match("@aws-sdk/client-s3"));
+        assert!(!glob.is_match("@aws-sdk"));
+        assert!(!glob.is_match("x@aws-sdk/client-s3"));
+        assert!(dependency_pattern("*.mycorp.io/api")
+            .unwrap()
+            .is_match("registry.mycorp.io/api"));
+
+        let url = dependency_pattern("/x/postgres@/").unwrap();
+        assert!(url.is_match("https://deno.land/x/postgres@v0.17.0/mod.ts"));
+        assert!(!url.is_match("https://deno.land/x/postgres/mod.ts"));
+
+        // Not a regex literal
+        assert!(dependency_pattern("/").unwrap().is_match("/"));
+        assert!(dependency_pattern("/(/").is_err());
+    }
+
+    #[test]
+    fn test_dependency_kind() {
+        assert_eq!(DependencyKind::of("pg"), DependencyKind::Exact);
+        assert_eq!(DependencyKind::of("/"), DependencyKind::Exact);
+        assert_eq!(DependencyKind::of("//"), DependencyKind::Exact);
+        assert_eq!(DependencyKind::of("@aws-sdk/*"), DependencyKind::Glob);
+        assert_eq!(DependencyKind::of("/x/postgres@/"), DependencyKind::Regex);
+        assert_eq!(DependencyKind::of("/x/*/"), DependencyKind::Regex);
     }
 
     #[test]
-    #[serial_test::serial]
     fn test_load_one_with_files_array() {
-        initialize();
-        clear_test_storage();
+        let mut registry = RuleRegistry::new();
 
         let rule = Rule {
             name: "test-rule2".to_string(),
@@ -198,16 +418,15 @@ mod tests {
             ..Default::default()
         };
 
-        register::register(rule.clone()).unwrap();
+        register::register(&mut registry, rule.clone()).unwrap();
 
-        load_one(&rule);
+        load_one(&mut registry, &rule);
 
         // Verify file matcher was added
-        let techs = RULES_TECHS.lock().unwrap();
-        assert_eq!(techs.len(), 1);
+        assert_eq!(registry.rules_techs.len(), 1);
 
         // Test the matcher
-        let matcher = &techs[0];
+        let matcher = &registry.rules_techs[0];
         let test_files = vec![ProviderFile {
             name: "package.json".to_string(),
             file_type: FileType::File,
@@ -222,10 +441,8 @@ mod tests {
         assert!(matcher(test_files).is_err());
     }
     #[test]
-    #[serial_test::serial]
     fn test_load_one_with_extensions() {
-        initialize();
-        clear_test_storage();
+        let mut registry = RuleRegistry::new();
 
         let rule = Rule {
             name: "test-rule3".to_string(),
@@ -235,18 +452,15 @@ mod tests {
             ..Default::default()
         };
 
-        // Register the rule first so it's available in LIST_INDEXED
-        register::register(rule.clone()).unwrap();
+        register::register(&mut registry, rule.clone()).unwrap();
 
-        load_one(&rule);
+        load_one(&mut registry, &rule);
 
         // Verify extension matcher was added
-        let extensions = RULES_EXTENSIONS.lock().unwrap();
-
-        assert_eq!(extensions.len(), 1);
+        assert_eq!(registry.rules_extensions.len(), 1);
 
         // Test the matcher
-        let matcher = &extensions[0];
+        let matcher = &registry.rules_extensions[0];
         let mut test_files = HashSet::new();
         test_files.insert("php".to_string());
         assert!(matcher(test_files.clone()).is_ok());
@@ -257,13 +471,13 @@ mod tests {
     }
 
     #[test]
-    #[serial_test::serial]
     fn test_load_one_with_component_detectors() {
-        initialize();
-        clear_test_storage();
+        let mut registry = RuleRegistry::new();
 
         let detector: ComponentMatcher =
-            |_files: &Vec<ProviderFile>, _provider: &dyn BaseProvider| Ok(Payload::new("test", ""));
+            |_files: &Vec<ProviderFile>, _provider: &dyn BaseProvider, _registry: &RuleRegistry| {
+                Ok(Payload::new("test", ""))
+            };
 
         let rule = Rule {
             name: "test-rule".to_string(),
@@ -273,47 +487,50 @@ mod tests {
             ..Default::default()
         };
 
-        load_one(&rule);
+        load_one(&mut registry, &rule);
 
         // Verify component detector was added
-        let components = RULES_COMPONENTS.lock().unwrap();
-        assert_eq!(components.len(), 1);
+        assert_eq!(registry.rules_components.len(), 1);
     }
 
     #[test]
-    #[serial_test::serial]
-    #[should_panic(expected = "empty dependency name")]
     fn test_load_one_with_empty_dependency_name() {
-        initialize();
-        clear_test_storage();
+        let mut registry = RuleRegistry::new();
 
         let rule = Rule {
             name: "test-rule".to_string(),
             tech: "python".to_string(),
             r#type: "language".to_string(),
-            dependencies: Some(vec![RuleDependency {
-                name: Some("".to_string()),
-                r#type: "python".to_string(),
-                ..Default::default()
-            }]),
+            dependencies: Some(vec![
+                RuleDependency {
+                    name: Some("".to_string()),
+                    r#type: "python".to_string(),
+                    ..Default::default()
+                },
+                RuleDependency {
+                    name: Some("/(/".to_string()),
+                    r#type: "python".to_string(),
+                    ..Default::default()
+                },
+            ]),
             ..Default::default()
         };
 
-        load_one(&rule);
+        // Skipped instead of panicking
+        load_one(&mut registry, &rule);
+        assert!(registry.dependencies["python"].is_empty());
     }
 
     #[test]
-    #[serial_test::serial]
     fn test_load_all_rules() {
-        initialize();
-        clear_test_storage();
+        let mut registry = RuleRegistry::new();
 
         // Create and register multiple test rules
         let rules = vec![
             Rule {
                 name: "Test Rule 1".to_string(),
-                tech: "tech1".to_string(),
-                r#type: "language".to_string(),
+                tech: "redis".to_string(),
+                r#type: "db".to_string(),
                 dependencies: Some(vec![RuleDependency {
                     name: Some("dep1".to_string()),
                     r#type: "python".to_string(),
@@ -323,8 +540,8 @@ mod tests {
             },
             Rule {
                 name: "Test Rule 2".to_string(),
-                tech: "tech2".to_string(),
-                r#type: "language".to_string(),
+                tech: "mongodb".to_string(),
+                r#type: "db".to_string(),
                 files: Some(RuleFiles::FilesArray {
                     files: vec!["test.file".to_string()],
                 }),
@@ -334,22 +551,203 @@ mod tests {
 
         // Register the rules
         for rule in rules {
-            register::register(rule).unwrap();
+            register::register(&mut registry, rule).unwrap();
         }
 
         // Call load_all_rules
-        load_all_rules(&REGISTERED_RULES.lock().unwrap());
+        load_all_rules(&mut registry);
 
         // Verify rules were loaded
-        let dependencies = DEPENDENCIES.lock().unwrap();
-        let python_deps = dependencies.get("python").unwrap();
+        let python_deps = registry.dependencies.get("python").unwrap();
         assert_eq!(python_deps.len(), 1);
-        assert_eq!(python_deps[0].tech, "tech1");
+        assert_eq!(python_deps.exact["dep1"], vec!["redis"]);
+
+        assert_eq!(registry.rules_techs.len(), 1);
+        assert_eq!(registry.raw_list.len(), 2);
+    }
 
-        let techs = RULES_TECHS.lock().unwrap();
-        assert_eq!(techs.len(), 1);
+    #[test]
+    fn test_parse_rules() {
+        let yaml = r#"
+tech: mycorp
+name: MyCorp SDK
+type: saas
+files: ["mycorp.json"]
+dependencies:
+  - { type: npm, name: "@mycorp/sdk", example: "@mycorp/sdk" }
+"#;
+        let rules = parse_rules(yaml, RuleFormat::Yaml).unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].tech, "mycorp");
+        assert_eq!(rules[0].r#type, "saas");
+        assert!(
+            matches!(&rules[0].files, Some(RuleFiles::FilesArray { files }) if files.len() == 1)
+        );
+        let deps = rules[0].dependencies.as_ref().unwrap();
+        assert_eq!(deps[0].r#type, "npm");
+        assert_eq!(deps[0].name.as_deref(), Some("@mycorp/sdk"));
+
+        let json = r#"[
+  {
+    "tech": "a", "name": "A", "type": "tool",
+    "files": { "regex": "^a\\.ya?ml$", "example": "a.yml" }
+  },
+  { "tech": "b", "name": "B", "type": "tool", "extensions": [".b"] }
+]"#;
+        let rules = parse_rules(json, RuleFormat::Json).unwrap();
+        assert_eq!(rules.len(), 2);
+        match &rules[0].files {
+            Some(RuleFiles::FilesRegex { files, example }) => {
+                assert!(files.is_match("a.yaml"));
+                assert_eq!(example, "a.yml");
+            }
+            other => panic!("unexpected files: {:?}", other),
+        }
+
+        let toml = r#"
+[[rules]]
+tech = "c"
+name = "C"
+type = "cloud"
+files = { regex = "^infra/c/", match_full_path = true }
+
+[[rules.dependencies]]
+type = "docker"
+name = "mycorp/c"
+"#;
+        let rules = parse_rules(toml, RuleFormat::Toml).unwrap();
+        assert!(matches!(
+            rules[0].files,
+            Some(RuleFiles::MatchFullPath { .. })
+        ));
+        assert_eq!(rules[0].dependencies.as_ref().unwrap().len(), 1);
+    }
 
-        let raw_list = RAW_LIST.lock().unwrap();
-        assert_eq!(raw_list.len(), 2);
+    #[test]
+    fn test_parse_rules_errors() {
+        let err =
+            parse_rules("tech: a\nname: A\ntype: tool\nfile: [a]", RuleFormat::Yaml).unwrap_err();
+        assert!(err.contains("rule #0 (a)"), "{}", err);
+        assert!(err.contains("unknown field `file`"), "{}", err);
+
+        let err = parse_rules("tech: a\nname: A", RuleFormat::Yaml).unwrap_err();
+        assert!(err.contains("missing field `type`"), "{}", err);
+
+        assert!(parse_rules(
+            r#"{ "tech": "a", "name": "A", "type": "tool", "files": { "regex": "(" } }"#,
+            RuleFormat::Json
+        )
+        .is_err());
+        assert!(parse_rules("- 1", RuleFormat::Yaml).is_err());
+        assert!(parse_rules("[", RuleFormat::Json).is_err());
+    }
+
+    #[test]
+    fn test_load_rules_from_str() {
+        let mut registry = RuleRegistry::with_defaults().unwrap();
+        let yaml = r#"
+custom_keys: [mycorp]
+rules:
+  - tech: mycorp
+    name: MyCorp SDK
+    type: saas
+    dependencies:
+      - { type: npm, name: "@mycorp/sdk" }
+"#;
+        assert_eq!(
+            load_rules_from_str(&mut registry, yaml, RuleFormat::Yaml),
+            Ok(1)
+        );
+        assert!(registry.get("mycorp").is_some());
+        assert!(registry.get("nodejs").is_some());
+
+        let pkgs = vec!["@mycorp/sdk".to_string()];
+        let techs = crate::rules::match_dependencies::match_dependencies(&pkgs, "npm", &registry);
+        assert!(techs.contains_key("mycorp"));
+
+        // Tech keys stay unique
+        assert!(load_rules_from_str(&mut registry, yaml, RuleFormat::Yaml).is_err());
+
+        // Only the keys the file declares are accepted
+        let yaml = "tech: othercorp\nname: OtherCorp\ntype: saas";
+        assert_eq!(
+            load_rules_from_str(&mut registry, yaml, RuleFormat::Yaml),
+            Err("othercorp: unknown tech key".to_string())
+        );
+        let yaml = "custom_keys: [othercorp]\ntech: othercorp";
+        assert!(load_rules_from_str(&mut registry, yaml, RuleFormat::Yaml).is_err());
+
+        // Every invalid rule is reported
+        let yaml = "- { tech: a, name: A, type: tool }\n- { tech: b, name: B, type: tool }";
+        assert_eq!(
+            load_rules_from_str(&mut registry, yaml, RuleFormat::Yaml),
+            Err("a: unknown tech key\nb: unknown tech key".to_string())
+        );
+    }
+
+    #[test]
+    fn test_load_rules_from_str_all_or_nothing() {
+        let mut registry = RuleRegistry::with_defaults().unwrap();
+        let rules = registry.registered_rules.len();
+        let matchers = registry.dependencies["npm"].len();
+
+        // The first rule is valid, the second one is not
+        let yaml = r#"
+custom_keys: [mycorp, othercorp]
+rules:
+  - tech: mycorp
+    name: MyCorp SDK
+    type: saas
+    dependencies:
+      - { type: npm, name: "@mycorp/sdk" }
+  - tech: othercorp
+    name: OtherCorp
+    type: saas
+    dependencies:
+      - { type: npm, name: "/[/" }
+"#;
+        assert!(load_rules_from_str(&mut registry, yaml, RuleFormat::Yaml).is_err());
+        assert_eq!(registry.registered_rules.len(), rules);
+        assert!(registry.get("mycorp").is_none());
+        assert!(!registry.name_to_key.contains_key("MyCorp SDK"));
+        assert!(registry.custom_keys.is_empty());
+        assert_eq!(registry.dependencies["npm"].len(), matchers);
+
+        // Rules of a file can depend on each other, e.g: the same dependency twice
+        let yaml = r#"
+custom_keys: [mycorp, othercorp]
+rules:
+  - { tech: mycorp, name: MyCorp, type: saas, dependencies: [{ type: npm, name: mycorp }] }
+  - { tech: othercorp, name: Other, type: saas, dependencies: [{ type: npm, name: mycorp }] }
+"#;
+        assert!(load_rules_from_str(&mut registry, yaml, RuleFormat::Yaml).is_err());
+        assert!(registry.get("mycorp").is_none());
+    }
+
+    #[test]
+    fn test_load_rules_from_dir() {
+        let dir = std::env::temp_dir().join(format!("rules-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("a.yml"),
+            "tech: a\nname: A\ntype: tool\nfiles: [a.json]",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("b.toml"),
+            "tech = \"b\"\nname = \"B\"\ntype = \"tool\"",
+        )
+        .unwrap();
+        fs::write(dir.join("README.md"), "# Rules").unwrap();
+
+        let mut registry = RuleRegistry::new().with_custom_keys(["a".to_string(), "b".to_string()]);
+        let count = load_rules_from_dir(&mut registry, &dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(count, Ok(2));
+        assert!(registry.get("a").is_some());
+        assert_eq!(registry.rules_techs.len(), 1);
+
+        assert!(load_rules_from_dir(&mut registry, &dir).is_err());
     }
 }
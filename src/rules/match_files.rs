@@ -1,9 +1,13 @@
-use crate::{provider::base::ProviderFile, rules::register::LIST_INDEXED, types::rule::Rule};
+use crate::{
+    provider::base::{FileType, ProviderFile},
+    types::rule::Rule,
+};
 use regex::Regex;
 use std::collections::HashSet;
 
+/// A name ending with `/` only matches a directory, e.g: `.vercel/`
 pub fn match_files(
-    key: &str,
+    rule: &Rule,
     files: &[ProviderFile],
     matches: &[String],
     match_full_path: bool,
@@ -15,13 +19,12 @@ pub fn match_files(
             &file.name
         };
 
-        if matches.contains(name) {
-            return LIST_INDEXED
-                .lock()
-                .unwrap()
-                .get(key)
-                .cloned()
-                .map(|rule| (rule, name.to_string()));
+        let found = matches.iter().any(|m| match m.strip_suffix('/') {
+            Some(dir) => file.file_type == FileType::Dir && dir == name,
+            None => m == name,
+        });
+        if found {
+            return Some((rule.clone(), name.to_string()));
         }
     }
 
@@ -29,7 +32,7 @@ pub fn match_files(
 }
 
 pub fn match_files_regex(
-    key: &str,
+    rule: &Rule,
     files: &[ProviderFile],
     pattern: &Regex,
     match_full_path: bool,
@@ -42,12 +45,7 @@ pub fn match_files_regex(
         };
 
         if pattern.is_match(name) {
-            return LIST_INDEXED
-                .lock()
-                .unwrap()
-                .get(key)
-                .cloned()
-                .map(|rule| (rule, name.to_string()));
+            return Some((rule.clone(), name.to_string()));
         }
     }
 
@@ -55,18 +53,13 @@ pub fn match_files_regex(
 }
 
 pub fn match_extensions(
-    key: &str,
+    rule: &Rule,
     list: &HashSet<String>,
     extensions: &HashSet<String>,
 ) -> Option<(Rule, String)> {
     for ext in list {
         if extensions.contains(ext) {
-            return LIST_INDEXED
-                .lock()
-                .unwrap()
-                .get(key)
-                .cloned()
-                .map(|rule| (rule, ext.to_string()));
+            return Some((rule.clone(), ext.to_string()));
         }
     }
 
@@ -77,24 +70,14 @@ pub fn match_extensions(
 mod tests {
     use super::*;
     use regex::RegexBuilder;
-    use std::sync::Once;
-
-    static INIT: Once = Once::new();
-
-    fn initialize() {
-        INIT.call_once(|| {
-            // Setup test data in LIST_INDEXED
-            let mut list = LIST_INDEXED.lock().unwrap();
-            list.insert(
-                "test_tech".to_string(),
-                Rule {
-                    tech: "test_tech".to_string(),
-                    name: "Test Tech".to_string(),
-                    r#type: "test".to_string(),
-                    ..Default::default()
-                },
-            );
-        });
+
+    fn create_test_rule() -> Rule {
+        Rule {
+            tech: "test_tech".to_string(),
+            name: "Test Tech".to_string(),
+            r#type: "test".to_string(),
+            ..Default::default()
+        }
     }
 
     fn create_test_files() -> Vec<ProviderFile> {
@@ -114,81 +97,105 @@ mod tests {
 
     #[test]
     fn test_match_files_found() {
-        initialize();
+        let rule = create_test_rule();
         let files = create_test_files();
         let matches = vec!["test.js".to_string()];
 
-        let result = match_files("test_tech", &files, &matches, false);
+        let result = match_files(&rule, &files, &matches, false);
         assert!(result.is_some());
 
-        let (rule, matched_file) = result.unwrap();
-        assert_eq!(rule.tech, "test_tech");
+        let (matched_rule, matched_file) = result.unwrap();
+        assert_eq!(matched_rule.tech, "test_tech");
         assert_eq!(matched_file, "test.js");
     }
 
     #[test]
     fn test_match_files_not_found() {
-        initialize();
+        let rule = create_test_rule();
         let files = create_test_files();
         let matches = vec!["nonexistent.file".to_string()];
 
-        let result = match_files("test_tech", &files, &matches, false);
+        let result = match_files(&rule, &files, &matches, false);
         assert!(result.is_none());
     }
 
     #[test]
     fn test_match_files_full_path() {
-        initialize();
+        let rule = create_test_rule();
         let files = create_test_files();
         let matches = vec!["src/main.rs".to_string()];
 
-        let result = match_files("test_tech", &files, &matches, true);
+        let result = match_files(&rule, &files, &matches, true);
         assert!(result.is_some());
 
-        let (rule, matched_file) = result.unwrap();
-        assert_eq!(rule.tech, "test_tech");
+        let (matched_rule, matched_file) = result.unwrap();
+        assert_eq!(matched_rule.tech, "test_tech");
         assert_eq!(matched_file, "src/main.rs");
     }
 
+    #[test]
+    fn test_match_files_directory() {
+        let rule = create_test_rule();
+        let mut files = create_test_files();
+        let matches = vec![".vercel/".to_string()];
+        assert!(match_files(&rule, &files, &matches, false).is_none());
+
+        // A file with the same name is not enough
+        files.push(ProviderFile {
+            name: ".vercel".to_string(),
+            file_type: FileType::File,
+            fp: ".vercel".to_string(),
+        });
+        assert!(match_files(&rule, &files, &matches, false).is_none());
+
+        files.push(ProviderFile {
+            name: ".vercel".to_string(),
+            file_type: FileType::Dir,
+            fp: ".vercel".to_string(),
+        });
+        let (_, matched_file) = match_files(&rule, &files, &matches, false).unwrap();
+        assert_eq!(matched_file, ".vercel");
+    }
+
     #[test]
     fn test_match_files_regex() {
-        initialize();
+        let rule = create_test_rule();
         let files = create_test_files();
         let pattern = RegexBuilder::new(r"test\.js$").build().unwrap();
 
-        let result = match_files_regex("test_tech", &files, &pattern, false);
+        let result = match_files_regex(&rule, &files, &pattern, false);
         assert!(result.is_some());
 
-        let (rule, matched_file) = result.unwrap();
-        assert_eq!(rule.tech, "test_tech");
+        let (matched_rule, matched_file) = result.unwrap();
+        assert_eq!(matched_rule.tech, "test_tech");
         assert_eq!(matched_file, "test.js");
     }
 
     #[test]
     fn test_match_extensions() {
-        initialize();
+        let rule = create_test_rule();
         let mut extensions = HashSet::new();
         extensions.insert("js".to_string());
         let mut list = HashSet::new();
         list.insert("js".to_string());
 
-        let result = match_extensions("test_tech", &list, &extensions);
+        let result = match_extensions(&rule, &list, &extensions);
         assert!(result.is_some());
 
-        let (rule, matched_ext) = result.unwrap();
-        assert_eq!(rule.tech, "test_tech");
+        let (matched_rule, matched_ext) = result.unwrap();
+        assert_eq!(matched_rule.tech, "test_tech");
         assert_eq!(matched_ext, "js");
     }
 
     #[test]
     fn test_match_extensions_not_found() {
-        initialize();
+        let rule = create_test_rule();
         let mut extensions = HashSet::new();
         extensions.insert("rs".to_string());
         let mut list = HashSet::new();
         list.insert("js".to_string());
 
-        let result = match_extensions("test_tech", &list, &extensions);
+        let result = match_extensions(&rule, &list, &extensions);
         assert!(result.is_none());
     }
 }
@@ -1,8 +1,14 @@
 pub mod analytics;
+pub mod ci;
+pub mod cloud;
 pub mod db;
+pub mod hosting;
 pub mod js;
 pub mod loader;
 pub mod match_dependencies;
 pub mod match_files;
 pub mod register;
+pub mod registry;
+pub mod self_test;
 pub mod spec;
+pub mod validate;
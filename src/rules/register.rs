@@ -1,54 +1,60 @@
+use crate::rules::registry::RuleRegistry;
+use crate::rules::validate::validate_rule;
 use crate::types::rule::Rule;
-use lazy_static::lazy_static;
-use std::collections::HashMap;
-use std::collections::HashSet;
-
-// Static storage equivalents
-lazy_static! {
-    static ref REGISTERED_TECH: std::sync::Mutex<HashSet<String>> =
-        std::sync::Mutex::new(HashSet::new());
-    pub static ref REGISTERED_RULES: std::sync::Mutex<Vec<Rule>> =
-        std::sync::Mutex::new(Vec::new());
-    pub static ref LIST_INDEXED: std::sync::Mutex<HashMap<String, Rule>> =
-        std::sync::Mutex::new(HashMap::new());
-    pub static ref NAME_TO_KEY: std::sync::Mutex<HashMap<String, String>> =
-        std::sync::Mutex::new(HashMap::new());
-}
-
-pub fn register(rule: Rule) -> Result<(), String> {
-    let mut registered_tech = REGISTERED_TECH.lock().unwrap();
 
-    if registered_tech.contains(&rule.tech) {
+pub fn register(registry: &mut RuleRegistry, rule: Rule) -> Result<(), String> {
+    if registry.list_indexed.contains_key(&rule.tech) {
         return Err(format!("Already registered {}", rule.name));
     }
+    if let Err(errors) = validate_rule(&rule, registry) {
+        let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        return Err(errors.join("\n"));
+    }
 
-    registered_tech.insert(rule.tech.clone());
-
-    REGISTERED_RULES.lock().unwrap().push(rule.clone());
-    LIST_INDEXED.lock().unwrap().insert(rule.tech.clone(), rule.clone());
-    NAME_TO_KEY.lock().unwrap().insert(rule.name.clone(), rule.tech);
+    registry.registered_rules.push(rule.clone());
+    registry.list_indexed.insert(rule.tech.clone(), rule.clone());
+    registry.name_to_key.insert(rule.name.clone(), rule.tech);
 
     Ok(())
 }
 
-pub fn register_all() -> Result<(), String> {
+pub fn register_all(registry: &mut RuleRegistry) -> Result<(), String> {
     // Import all rule registration functions
     use crate::rules::*; // This will import any other rule modules you add
 
     // Register each rule
     let registrations = vec![
-        analytics::amplitude::register_amplitude(),
-        js::react::register_react(),
-        js::typescript::register_typescript(),
-        spec::nodejs::register_nodejs(),
-        spec::rust::register_rust(),
-        db::postgres::register_postgres(),
-        spec::docker::register_docker(),
+        analytics::amplitude::register_amplitude(registry),
+        ci::circleci::register_circleci(registry),
+        js::react::register_react(registry),
+        js::typescript::register_typescript(registry),
+        spec::nodejs::register_nodejs(registry),
+        spec::rust::register_rust(registry),
+        db::postgres::register_postgres(registry),
+        cloud::aws::register_aws(registry),
+        cloud::aws::register_aws_rds(registry),
+        cloud::aws::register_aws_s3(registry),
+        cloud::gcp::register_gcp(registry),
+        cloud::gcp::register_gcp_sql(registry),
+        cloud::gcp::register_gcp_gcs(registry),
+        cloud::gcp::register_gcp_appengine(registry),
+        hosting::vercel::register_vercel(registry),
+        hosting::netlify::register_netlify(registry),
+        hosting::flyio::register_flyio(registry),
+        spec::docker::register_docker(registry),
+        spec::github_actions::register_github_actions(registry),
+        spec::golang::register_golang(registry),
+        spec::python::register_python(registry),
+        spec::ruby::register_ruby(registry),
+        spec::php::register_php(registry),
+        spec::deno::register_deno(registry),
+        spec::terraform::register_terraform(registry),
     ];
 
-    // Check if any registration failed
-    for result in registrations {
-        result?;
+    // Report every rule that failed, not only the first one
+    let errors: Vec<String> = registrations.into_iter().filter_map(|r| r.err()).collect();
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
     }
 
     Ok(())
@@ -57,177 +63,148 @@ pub fn register_all() -> Result<(), String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::Once;
-
-    static INIT: Once = Once::new();
-
-    fn initialize() {
-        INIT.call_once(|| {
-            // Currently empty as lazy_static! handles our initialization
-            // Keep this function if you anticipate needing test-specific setup in the future
-        });
-    }
-
-    fn clear_test_storage() {
-        REGISTERED_TECH.lock().unwrap().clear();
-        REGISTERED_RULES.lock().unwrap().clear();
-        LIST_INDEXED.lock().unwrap().clear();
-        NAME_TO_KEY.lock().unwrap().clear();
-    }
 
     #[test]
-    #[serial_test::serial]
     fn test_register_success() {
-        initialize();
-        clear_test_storage();
+        let mut registry = RuleRegistry::new().with_custom_keys(["test_tech".to_string()]);
         // Create a test rule
         let rule = Rule {
             name: "Test Rule".to_string(),
             tech: "test_tech".to_string(),
-            r#type: "test_type".to_string(),
+            r#type: "tool".to_string(),
             ..Default::default()
         };
 
         // Test successful registration
-        let result = register(rule);
+        let result = register(&mut registry, rule);
         assert!(result.is_ok());
     }
 
     #[test]
-    #[serial_test::serial]
     fn test_register_duplicate() {
-        initialize();
-        clear_test_storage();
+        let mut registry = RuleRegistry::new().with_custom_keys(["test_tech_2".to_string()]);
         // Create a test rule
         let rule1 = Rule {
             name: "Test Rule 2".to_string(),
             tech: "test_tech_2".to_string(),
-            r#type: "test_type2".to_string(),
+            r#type: "tool".to_string(),
             ..Default::default()
         };
 
         let rule2 = Rule {
             name: "Test Rule 3".to_string(),
             tech: "test_tech_2".to_string(),
-            r#type: "test_type3".to_string(),
+            r#type: "db".to_string(),
             ..Default::default()
         };
 
         // First registration should succeed
-        let result1 = register(rule1);
+        let result1 = register(&mut registry, rule1);
         assert!(result1.is_ok());
 
         // Second registration with same tech should fail
-        let result2 = register(rule2);
+        let result2 = register(&mut registry, rule2);
         assert!(result2.is_err());
     }
 
     #[test]
-    #[serial_test::serial]
     fn test_register_all() {
-        initialize();
-        clear_test_storage();
+        let mut registry = RuleRegistry::new();
         // Test the register_all function
-        let result = register_all();
+        let result = register_all(&mut registry);
         assert!(result.is_ok());
 
         // Verify that rules were actually registered
-        let registered_rules = REGISTERED_RULES.lock().unwrap();
-        println!("Registered rules: {:#?}", *registered_rules);
+        assert!(!registry.registered_rules.is_empty());
 
-        assert!(!registered_rules.is_empty());
+        // A second call on the same registry finds the duplicates
+        assert!(register_all(&mut registry).is_err());
     }
 
     #[test]
-    #[serial_test::serial]
-    fn test_static_storage_variables() {
-        initialize();
-        clear_test_storage();
+    fn test_registry_storage() {
+        let mut registry = RuleRegistry::new().with_custom_keys(["storage_test_tech".to_string()]);
 
         // Create a test rule
         let rule = Rule {
             name: "Storage Test Rule".to_string(),
             tech: "storage_test_tech".to_string(),
-            r#type: "test_type".to_string(),
+            r#type: "tool".to_string(),
             ..Default::default()
         };
 
         // Register the rule
-        let result = register(rule.clone());
+        let result = register(&mut registry, rule.clone());
         assert!(result.is_ok());
 
-        // Test REGISTERED_RULES
-        {
-            let registered_rules = REGISTERED_RULES.lock().unwrap();
-            assert_eq!(registered_rules.len(), 1);
-            assert_eq!(registered_rules[0].name, "Storage Test Rule");
-            assert_eq!(registered_rules[0].tech, "storage_test_tech");
-        }
-
-        // Test LIST_INDEXED
-        {
-            let list_indexed = LIST_INDEXED.lock().unwrap();
-            assert_eq!(list_indexed.len(), 1);
-            assert!(list_indexed.contains_key("storage_test_tech"));
-            let indexed_rule = list_indexed.get("storage_test_tech").unwrap();
-            assert_eq!(indexed_rule.name, "Storage Test Rule");
-        }
-
-        // Test NAME_TO_KEY
-        {
-            let name_to_key = NAME_TO_KEY.lock().unwrap();
-            assert_eq!(name_to_key.len(), 1);
-            assert!(name_to_key.contains_key("Storage Test Rule"));
-            assert_eq!(
-                name_to_key.get("Storage Test Rule").unwrap(),
-                "storage_test_tech"
-            );
-        }
+        // Test registered_rules
+        assert_eq!(registry.registered_rules.len(), 1);
+        assert_eq!(registry.registered_rules[0].name, "Storage Test Rule");
+        assert_eq!(registry.registered_rules[0].tech, "storage_test_tech");
+
+        // Test list_indexed
+        assert_eq!(registry.list_indexed.len(), 1);
+        assert!(registry.list_indexed.contains_key("storage_test_tech"));
+        let indexed_rule = registry.list_indexed.get("storage_test_tech").unwrap();
+        assert_eq!(indexed_rule.name, "Storage Test Rule");
+
+        // Test name_to_key
+        assert_eq!(registry.name_to_key.len(), 1);
+        assert!(registry.name_to_key.contains_key("Storage Test Rule"));
+        assert_eq!(
+            registry.name_to_key.get("Storage Test Rule").unwrap(),
+            "storage_test_tech"
+        );
     }
 
     #[test]
-    #[serial_test::serial]
     fn test_multiple_rules_storage() {
-        initialize();
-        clear_test_storage();
+        let mut registry =
+            RuleRegistry::new().with_custom_keys(["tech1".to_string(), "tech2".to_string()]);
 
         // Create two test rules
         let rule1 = Rule {
             name: "First Rule".to_string(),
             tech: "tech1".to_string(),
-            r#type: "test_type".to_string(),
+            r#type: "tool".to_string(),
             ..Default::default()
         };
 
         let rule2 = Rule {
             name: "Second Rule".to_string(),
             tech: "tech2".to_string(),
-            r#type: "test_type".to_string(),
+            r#type: "tool".to_string(),
             ..Default::default()
         };
 
         // Register both rules
-        register(rule1.clone()).unwrap();
-        register(rule2.clone()).unwrap();
-
-        // Verify all storage variables contain both rules
-        {
-            let registered_rules = REGISTERED_RULES.lock().unwrap();
-            assert_eq!(registered_rules.len(), 2);
-        }
-
-        {
-            let list_indexed = LIST_INDEXED.lock().unwrap();
-            assert_eq!(list_indexed.len(), 2);
-            assert!(list_indexed.contains_key("tech1"));
-            assert!(list_indexed.contains_key("tech2"));
-        }
-
-        {
-            let name_to_key = NAME_TO_KEY.lock().unwrap();
-            assert_eq!(name_to_key.len(), 2);
-            assert_eq!(name_to_key.get("First Rule").unwrap(), "tech1");
-            assert_eq!(name_to_key.get("Second Rule").unwrap(), "tech2");
-        }
+        register(&mut registry, rule1.clone()).unwrap();
+        register(&mut registry, rule2.clone()).unwrap();
+
+        // Verify all storage contains both rules
+        assert_eq!(registry.registered_rules.len(), 2);
+
+        assert_eq!(registry.list_indexed.len(), 2);
+        assert!(registry.list_indexed.contains_key("tech1"));
+        assert!(registry.list_indexed.contains_key("tech2"));
+
+        assert_eq!(registry.name_to_key.len(), 2);
+        assert_eq!(registry.name_to_key.get("First Rule").unwrap(), "tech1");
+        assert_eq!(registry.name_to_key.get("Second Rule").unwrap(), "tech2");
+    }
+
+    #[test]
+    fn test_register_invalid() {
+        let mut registry = RuleRegistry::new();
+        let rule = Rule {
+            name: "Unknown".to_string(),
+            tech: "unknown_tech".to_string(),
+            r#type: "db".to_string(),
+            ..Default::default()
+        };
+
+        let result = register(&mut registry, rule);
+        assert_eq!(result, Err("unknown_tech: unknown tech key".to_string()));
+        assert!(registry.registered_rules.is_empty());
     }
 }
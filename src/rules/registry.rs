@@ -0,0 +1,154 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::rules::loader::{load_all_rules, DependencyMatchers, RuleEntry};
+use crate::rules::register::{register, register_all};
+use crate::types::rule::{ComponentMatcher, ExtensionMatcher, Rule, TechMatcher};
+
+/// Supported dependency types, each one gets its own list of matchers.
+pub const DEPENDENCY_TYPES: [&str; 11] = [
+    "terraform.resource",
+    "deno",
+    "docker",
+    "golang",
+    "npm",
+    "php",
+    "python",
+    "ruby",
+    "rust",
+    "terraform",
+    "githubAction",
+];
+
+/// All the rules used by an analysis, and the matchers built from them.
+///
+/// Rules are first registered (indexed by tech), then loaded (turned into matchers).
+/// Each registry is independent, so analyses with different rule sets can run side by side.
+pub struct RuleRegistry {
+    pub registered_rules: Vec<Rule>,
+    pub list_indexed: HashMap<String, Rule>,
+    pub name_to_key: HashMap<String, String>,
+
+    pub rules_techs: Vec<TechMatcher>,
+    pub rules_extensions: Vec<ExtensionMatcher>,
+    pub rules_components: Vec<ComponentMatcher>,
+    pub dependencies: HashMap<String, DependencyMatchers>,
+    pub raw_list: Vec<RuleEntry>,
+
+    /// Also report the dependencies of dependencies, when a lockfile lists them.
+    pub transitive: bool,
+    /// Tech keys accepted besides `ALLOWED_KEYS`, for in-house rules.
+    pub custom_keys: HashSet<String>,
+}
+
+impl RuleRegistry {
+    /// Empty registry, without any rule.
+    pub fn new() -> Self {
+        Self {
+            registered_rules: Vec::new(),
+            list_indexed: HashMap::new(),
+            name_to_key: HashMap::new(),
+            rules_techs: Vec::new(),
+            rules_extensions: Vec::new(),
+            rules_components: Vec::new(),
+            dependencies: DEPENDENCY_TYPES
+                .iter()
+                .map(|t| (t.to_string(), DependencyMatchers::default()))
+                .collect(),
+            raw_list: Vec::new(),
+            transitive: false,
+            custom_keys: HashSet::new(),
+        }
+    }
+
+    /// Registry with all the built-in rules, ready to be used.
+    pub fn with_defaults() -> Result<Self, String> {
+        let mut registry = Self::new();
+        register_all(&mut registry)?;
+        load_all_rules(&mut registry);
+        Ok(registry)
+    }
+
+    /// Registry with only the given rules, ready to be used.
+    pub fn from_rules(rules: Vec<Rule>) -> Result<Self, String> {
+        let mut registry = Self::new();
+        for rule in rules {
+            register(&mut registry, rule)?;
+        }
+        load_all_rules(&mut registry);
+        Ok(registry)
+    }
+
+    /// Report transitive dependencies found in lockfiles, they are never matched against rules.
+    pub fn with_transitive(mut self, transitive: bool) -> Self {
+        self.transitive = transitive;
+        self
+    }
+
+    /// Accept rules for techs that are not in `ALLOWED_KEYS`, e.g: an internal SDK.
+    pub fn with_custom_keys<I: IntoIterator<Item = String>>(mut self, keys: I) -> Self {
+        self.custom_keys.extend(keys);
+        self
+    }
+
+    /// Find a registered rule by its tech key.
+    pub fn get(&self, tech: &str) -> Option<&Rule> {
+        self.list_indexed.get(tech)
+    }
+}
+
+impl Default for RuleRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::match_dependencies::match_dependencies;
+    use crate::types::rule::RuleDependency;
+
+    #[test]
+    fn test_new() {
+        let registry = RuleRegistry::new();
+        assert!(registry.registered_rules.is_empty());
+        assert_eq!(registry.dependencies.len(), DEPENDENCY_TYPES.len());
+        assert!(registry.dependencies.values().all(|v| v.is_empty()));
+    }
+
+    #[test]
+    fn test_with_defaults() {
+        let registry = RuleRegistry::with_defaults().unwrap();
+        assert!(!registry.registered_rules.is_empty());
+        assert!(registry.get("nodejs").is_some());
+        assert!(!registry.rules_components.is_empty());
+
+        // Can be built many times in the same process
+        assert!(RuleRegistry::with_defaults().is_ok());
+    }
+
+    #[test]
+    fn test_registries_are_independent() {
+        let rule = |tech: &str, dep: &str| Rule {
+            name: tech.to_string(),
+            tech: tech.to_string(),
+            r#type: "db".to_string(),
+            dependencies: Some(vec![RuleDependency {
+                r#type: "npm".to_string(),
+                name: Some(dep.to_string()),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        let first = RuleRegistry::from_rules(vec![rule("mysql", "dep")]).unwrap();
+        let second = RuleRegistry::from_rules(vec![rule("mongodb", "dep")]).unwrap();
+
+        let pkgs = vec!["dep".to_string()];
+        assert!(match_dependencies(&pkgs, "npm", &first).contains_key("mysql"));
+        assert!(match_dependencies(&pkgs, "npm", &second).contains_key("mongodb"));
+        assert!(!match_dependencies(&pkgs, "npm", &second).contains_key("mysql"));
+
+        assert!(RuleRegistry::from_rules(vec![rule("mysql", "a"), rule("mysql", "b")]).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::payload::match_all_files::match_all_files;
+use crate::provider::base::BaseProvider;
+use crate::provider::fake::FakeProvider;
+use crate::rules::match_dependencies::match_dependencies;
+use crate::rules::registry::RuleRegistry;
+use crate::rules::validate::{example_names, RuleError};
+use crate::types::rule::{Rule, RuleFiles};
+
+/// Run a loaded rule against what its examples describe, through the matchers of the registry.
+///
+/// Each dependency is matched from its `example`, each file from a folder
+/// holding only this file, each extension from an `example.<ext>` file. A rule that can't
+/// find its own examples would not find anything in a real project either.
+pub fn self_test(rule: &Rule, registry: &RuleRegistry) -> Result<(), Vec<RuleError>> {
+    let tech = || rule.tech.clone();
+    let mut errors = Vec::new();
+
+    for dep in rule.dependencies.iter().flatten() {
+        let Some(name) = &dep.name else {
+            continue;
+        };
+        // Even an exact name: matching it against itself would not test anything
+        let Some(example) = dep.example.clone() else {
+            errors.push(RuleError::MissingExample {
+                tech: tech(),
+                pattern: name.clone(),
+            });
+            continue;
+        };
+
+        let names = example_names(&dep.r#type, &example);
+        if !match_dependencies(&names, &dep.r#type, registry).contains_key(&rule.tech) {
+            errors.push(RuleError::ExampleMismatch {
+                tech: tech(),
+                example,
+                pattern: name.clone(),
+            });
+        }
+    }
+
+    // (example, pattern)
+    let mut files: Vec<(String, String)> = Vec::new();
+    match &rule.files {
+        Some(RuleFiles::FilesArray { files: names }) => {
+            files.extend(names.iter().map(|name| (name.clone(), name.clone())));
+        }
+        Some(RuleFiles::FilesRegex {
+            files: regex,
+            example,
+        })
+        | Some(RuleFiles::MatchFullPath {
+            files: regex,
+            example,
+            ..
+        }) => {
+            if example.is_empty() {
+                errors.push(RuleError::MissingExample {
+                    tech: tech(),
+                    pattern: regex.to_string(),
+                });
+            } else {
+                files.push((example.clone(), regex.to_string()));
+            }
+        }
+        Some(RuleFiles::NoFiles) | None => {}
+    }
+    for ext in rule.extensions.iter().flatten() {
+        files.push((format!("example.{}", ext), ext.clone()));
+    }
+
+    for (example, pattern) in files {
+        if !matches_tree(&example, &rule.tech, registry) {
+            errors.push(RuleError::ExampleMismatch {
+                tech: tech(),
+                example,
+                pattern,
+            });
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Whether a project holding only `path`, relative to its root, is enough to find `tech`
+fn matches_tree(path: &str, tech: &str, registry: &RuleRegistry) -> bool {
+    let provider = example_tree(path);
+    let dir = Path::new("/").join(path);
+    let dir = dir.parent().unwrap_or(Path::new("/")).to_string_lossy();
+    let files = provider.list_dir(&format!("{}/", dir.trim_end_matches('/')));
+
+    match_all_files(&files, &provider.base_path(), registry).contains_key(tech)
+}
+
+/// Every folder leading to `path`, and the file itself (empty), or the folder for `name/`
+fn example_tree(path: &str) -> FakeProvider {
+    let mut paths: HashMap<String, Vec<String>> = HashMap::new();
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    let mut dir = "/".to_string();
+    for (i, segment) in segments.iter().enumerate() {
+        let is_file = i == segments.len() - 1 && !path.ends_with('/');
+        let entry = if is_file {
+            segment.to_string()
+        } else {
+            format!("{}/", segment)
+        };
+        paths.entry(dir.clone()).or_default().push(entry.clone());
+        dir.push_str(&entry);
+    }
+
+    let mut files = HashMap::new();
+    if !dir.ends_with('/') {
+        files.insert(dir, String::new());
+    }
+    FakeProvider::new(paths, files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::base::FileType;
+    use crate::types::rule::RuleDependency;
+    use regex::Regex;
+
+    #[test]
+    fn test_builtin_rules_match_their_examples() {
+        let registry = RuleRegistry::with_defaults().unwrap();
+        for rule in &registry.registered_rules {
+            if let Err(errors) = self_test(rule, &registry) {
+                panic!(
+                    "{:?}",
+                    errors.iter().map(|e| e.to_string()).collect::<Vec<_>>()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_self_test() {
+        let rule = Rule {
+            tech: "mongodb".to_string(),
+            name: "MongoDB".to_string(),
+            r#type: "db".to_string(),
+            dependencies: Some(vec![
+                RuleDependency {
+                    r#type: "npm".to_string(),
+                    name: Some("mongodb".to_string()),
+                    example: Some("mongodb".to_string()),
+                },
+                RuleDependency {
+                    r#type: "npm".to_string(),
+                    name: Some("mongoose".to_string()),
+                    ..Default::default()
+                },
+                RuleDependency {
+                    r#type: "deno".to_string(),
+                    name: Some("/x/mongo@/".to_string()),
+                    ..Default::default()
+                },
+            ]),
+            files: Some(RuleFiles::MatchFullPath {
+                match_full_path: true,
+                files: Regex::new(r"^mongo/mongod\.conf$").unwrap(),
+                example: "mongo/mongod.conf".to_string(),
+            }),
+            // Extensions are written without the dot
+            extensions: Some(vec!["bson".to_string(), ".mongo".to_string()]),
+            ..Default::default()
+        };
+        let registry = RuleRegistry::from_rules(vec![rule.clone()]).unwrap();
+
+        let errors: Vec<String> =
+            self_test(&rule, &registry).unwrap_err().iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "mongodb: no example to test \"mongoose\" with",
+                "mongodb: no example to test \"/x/mongo@/\" with",
+                "mongodb: example \"example..mongo\" does not match \".mongo\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_example_tree() {
+        let provider = example_tree(".github/workflows/ci.yml");
+        assert_eq!(provider.list_dir("/")[0].name, ".github");
+        let files = provider.list_dir("/.github/workflows/");
+        assert_eq!(files[0].fp, "/.github/workflows/ci.yml");
+        assert_eq!(
+            provider.open("/.github/workflows/ci.yml"),
+            Some(String::new())
+        );
+
+        let provider = example_tree(".vercel/");
+        assert_eq!(provider.list_dir("/")[0].file_type, FileType::Dir);
+        assert!(provider.open("/.vercel/").is_none());
+    }
+}
@@ -0,0 +1,378 @@
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    payload::payload::{dependency, Payload},
+    provider::base::{folder_name, BaseProvider, ProviderFile},
+    rules::{
+        match_dependencies::match_dependencies, registry::RuleRegistry, spec::jsonc::strip_jsonc,
+    },
+};
+
+const CONFIGS: [&str; 2] = ["deno.json", "deno.jsonc"];
+const LOCKFILE: &str = "deno.lock";
+
+#[derive(Debug, Deserialize)]
+struct DenoJson {
+    name: Option<String>,
+    imports: Option<HashMap<String, String>>,
+    #[serde(rename = "importMap")]
+    import_map: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportMap {
+    imports: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DenoLock {
+    version: Option<String>,
+    #[serde(default)]
+    remote: HashMap<String, String>,
+    /// v2: npm specifiers without the `npm:` prefix
+    npm: Option<LockSpecifiers>,
+    /// v3: npm and jsr specifiers, prefixed
+    packages: Option<LockSpecifiers>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LockSpecifiers {
+    #[serde(default)]
+    specifiers: HashMap<String, String>,
+}
+
+pub fn detect_deno_component(
+    files: &Vec<ProviderFile>,
+    provider: &dyn BaseProvider,
+    registry: &RuleRegistry,
+) -> Result<Payload, bool> {
+    let mut config: Option<(&ProviderFile, DenoJson)> = None;
+    let mut lock: Option<(&ProviderFile, DenoLock)> = None;
+
+    for file in files {
+        let is_config = CONFIGS.contains(&file.name.as_str());
+        if !is_config && file.name != LOCKFILE {
+            continue;
+        }
+
+        let content = match provider.open(&file.fp) {
+            Some(content) => content,
+            None => continue,
+        };
+
+        if is_config {
+            match serde_json::from_str::<DenoJson>(&strip_jsonc(&content)) {
+                Ok(deno_json) => config = Some((file, deno_json)),
+                Err(e) => log::warn!("Failed to parse {}: {} - {}", file.name, file.fp, e),
+            }
+        } else {
+            match serde_json::from_str::<DenoLock>(&content) {
+                Ok(deno_lock) => lock = Some((file, deno_lock)),
+                Err(e) => log::warn!("Failed to parse deno.lock: {} - {}", file.fp, e),
+            }
+        }
+    }
+
+    let first = match (&config, &lock) {
+        (Some((file, _)), _) | (None, Some((file, _))) => *file,
+        (None, None) => return Err(false),
+    };
+
+    let mut specifiers: Vec<String> = Vec::new();
+    let mut name = None;
+    if let Some((file, deno_json)) = config {
+        name = deno_json.name;
+        specifiers.extend(deno_json.imports.unwrap_or_default().into_values());
+        if let Some(import_map) = deno_json.import_map {
+            specifiers.extend(read_import_map(file, &import_map, provider));
+        }
+    }
+
+    let mut resolved: HashMap<String, String> = HashMap::new();
+    let mut paths = vec![first.fp.clone()];
+    if let Some((file, deno_lock)) = lock {
+        log::debug!(
+            "Reading deno.lock v{}",
+            deno_lock.version.as_deref().unwrap_or("?")
+        );
+        paths.push(file.fp.clone());
+
+        specifiers.extend(deno_lock.remote.into_keys());
+        for (requested, locked) in deno_lock.npm.map(|n| n.specifiers).unwrap_or_default() {
+            resolved.insert(format!("npm:{}", requested), format!("npm:{}", locked));
+        }
+        resolved.extend(deno_lock.packages.map(|p| p.specifiers).unwrap_or_default());
+        specifiers.extend(resolved.keys().cloned());
+    }
+
+    let name = name.unwrap_or_else(|| folder_name(&first.fp, provider));
+
+    let mut deps: BTreeSet<Vec<String>> = BTreeSet::new();
+    let mut deno_matches: Vec<String> = Vec::new();
+    let mut npm_matches: Vec<String> = Vec::new();
+    for specifier in specifiers {
+        // Anything else is local (./utils.ts) or built-in (node:fs)
+        let Some((r#type, name, declared)) = parse_specifier(&specifier) else {
+            continue;
+        };
+        let locked = match resolved.get(&specifier) {
+            Some(locked) => parse_specifier(locked).map(|(_, _, version)| version),
+            // A remote module is fetched at the version written in its url
+            None if specifier.contains("://") && declared != "latest" => Some(declared.clone()),
+            None => None,
+        };
+
+        if r#type == "npm" {
+            npm_matches.push(name.clone());
+        } else {
+            deno_matches.push(specifier.clone());
+        }
+        deps.insert(dependency(
+            r#type,
+            &name,
+            &locked.unwrap_or_default(),
+            &declared,
+            "",
+        ));
+    }
+
+    let mut pl = Payload::new(&name, &paths[0]);
+    pl.path.extend(paths);
+
+    pl.add_techs(
+        &match_dependencies(&deno_matches, "deno", registry),
+        registry,
+    );
+    pl.add_techs(&match_dependencies(&npm_matches, "npm", registry), registry);
+    pl.dependencies = deps.into_iter().collect();
+
+    Ok(pl)
+}
+
+fn read_import_map(config: &ProviderFile, path: &str, provider: &dyn BaseProvider) -> Vec<String> {
+    if path.contains("://") {
+        log::debug!("Remote import map ignored: {}", path);
+        return Vec::new();
+    }
+
+    // Collecting the components drops the `./` of the relative path
+    let fp: PathBuf = Path::new(&config.fp)
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(path)
+        .components()
+        .collect();
+    let fp = fp.to_string_lossy();
+    let Some(content) = provider.open(&fp) else {
+        log::warn!("Failed to open import map: {}", fp);
+        return Vec::new();
+    };
+
+    match serde_json::from_str::<ImportMap>(&strip_jsonc(&content)) {
+        Ok(import_map) => import_map.imports.unwrap_or_default().into_values().collect(),
+        Err(e) => {
+            log::warn!("Failed to parse import map: {} - {}", fp, e);
+            Vec::new()
+        }
+    }
+}
+
+/// Type, name and version of an import, e.g: `npm:pg@8` is the npm package `pg` at `8`.
+fn parse_specifier(specifier: &str) -> Option<(&'static str, String, String)> {
+    if let Some(package) = specifier.strip_prefix("npm:") {
+        let (name, version) = split_package(package.trim_start_matches('/'));
+        // Peer dependencies are appended to the resolved version, e.g: 1.0.0_react@18.2.0
+        let version = version.split('_').next().unwrap_or_default().to_string();
+        Some(("npm", name, version))
+    } else if let Some(package) = specifier.strip_prefix("jsr:") {
+        let (name, version) = split_package(package.trim_start_matches('/'));
+        Some(("deno", format!("jsr:{}", name), version))
+    } else if specifier.starts_with("https://") || specifier.starts_with("http://") {
+        let (name, version) = parse_url(specifier);
+        Some(("deno", name, version))
+    } else {
+        None
+    }
+}
+
+/// Split `name@version/sub/path`, the name can be scoped (`@std/path@1.0.0`).
+fn split_package(package: &str) -> (String, String) {
+    let scoped = package.starts_with('@');
+    match package[1.min(package.len())..].find('@') {
+        Some(idx) => {
+            let name = &package[..idx + 1];
+            let version = package[idx + 2..].split('/').next().unwrap_or_default();
+            (name.to_string(), version.to_string())
+        }
+        None => {
+            let segments = if scoped { 2 } else { 1 };
+            let name: Vec<&str> = package.split('/').take(segments).collect();
+            (name.join("/"), "latest".to_string())
+        }
+    }
+}
+
+/// Module name and version of a remote import, e.g:
+/// `https://deno.land/x/postgres@v0.17.0/mod.ts` is `deno.land/x/postgres` at `v0.17.0`
+fn parse_url(url: &str) -> (String, String) {
+    let without_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let path = without_scheme.split(['?', '#']).next().unwrap_or_default();
+
+    let mut name: Vec<&str> = Vec::new();
+    for (i, segment) in path.split('/').enumerate() {
+        // The @ of a scope is not a version separator
+        let at = segment.get(1..).and_then(|s| s.find('@'));
+        match at {
+            Some(at) if i > 0 => {
+                name.push(&segment[..at + 1]);
+                return (name.join("/"), segment[at + 2..].to_string());
+            }
+            _ => name.push(segment),
+        }
+    }
+
+    (
+        name.join("/").trim_end_matches('/').to_string(),
+        "latest".to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{fake::FakeProvider, fs::FsProvider};
+
+    fn dep(r#type: &str, name: &str, resolved: &str, declared: &str) -> Vec<String> {
+        dependency(r#type, name, resolved, declared, "")
+    }
+
+    #[test]
+    fn test_detect_deno_component() {
+        let deno_jsonc = r#"{
+  // Published on jsr
+  "name": "@acme/api",
+  "importMap": "./import_map.json", /* shared with the editor */
+}"#;
+        let import_map = r#"{
+  "imports": {
+    "std/": "https://deno.land/std@0.200.0/",
+    "oak": "https://deno.land/x/oak@v12.6.0/mod.ts",
+    "pg": "npm:pg@8",
+    "@std/path": "jsr:@std/path@^0.220",
+    "utils": "./utils.ts",
+  }
+}"#;
+        let deno_lock = r#"{
+  "version": "3",
+  "packages": {
+    "specifiers": {
+      "npm:pg@8": "npm:pg@8.11.3",
+      "npm:@types/pg@8": "npm:@types/pg@8.10.2_pg@8.11.3",
+      "jsr:@std/path@^0.220": "jsr:@std/path@0.220.1"
+    },
+    "npm": {}
+  },
+  "remote": {
+    "https://deno.land/x/oak@v12.6.0/mod.ts": "abc",
+    "https://deno.land/x/oak@v12.6.0/router.ts": "def"
+  }
+}"#;
+
+        let provider = FakeProvider::new(
+            HashMap::from_iter([(
+                "/app".to_string(),
+                vec![
+                    "deno.jsonc".to_string(),
+                    "deno.lock".to_string(),
+                    "import_map.json".to_string(),
+                ],
+            )]),
+            HashMap::from_iter([
+                ("/app/deno.jsonc".to_string(), deno_jsonc.to_string()),
+                ("/app/import_map.json".to_string(), import_map.to_string()),
+                ("/app/deno.lock".to_string(), deno_lock.to_string()),
+            ]),
+        );
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let result =
+            detect_deno_component(&provider.list_dir("/app"), &provider, &registry).unwrap();
+
+        assert_eq!(result.name, "@acme/api");
+        assert_eq!(result.path.len(), 2);
+        assert_eq!(
+            result.dependencies,
+            vec![
+                dep("deno", "deno.land/std", "0.200.0", "0.200.0"),
+                dep("deno", "deno.land/x/oak", "v12.6.0", "v12.6.0"),
+                dep("deno", "jsr:@std/path", "0.220.1", "^0.220"),
+                dep("npm", "@types/pg", "8.10.2", "8"),
+                dep("npm", "pg", "8.11.3", "8"),
+            ]
+        );
+        assert!(result.techs.contains("postgresql"));
+    }
+
+    #[test]
+    fn test_detect_deno_lock_v2() {
+        let deno_lock = r#"{
+  "version": "2",
+  "remote": {},
+  "npm": {
+    "specifiers": { "chalk@5": "chalk@5.3.0" },
+    "packages": { "chalk@5.3.0": { "integrity": "sha512", "dependencies": {} } }
+  }
+}"#;
+
+        let provider = FakeProvider::new(
+            HashMap::from_iter([("/cli".to_string(), vec!["deno.lock".to_string()])]),
+            HashMap::from_iter([("/cli/deno.lock".to_string(), deno_lock.to_string())]),
+        );
+
+        let result =
+            detect_deno_component(&provider.list_dir("/cli"), &provider, &RuleRegistry::new())
+                .unwrap();
+
+        assert_eq!(result.name, "cli");
+        assert_eq!(result.dependencies, vec![dep("npm", "chalk", "5.3.0", "5")]);
+    }
+
+    #[test]
+    fn test_detect_deno_fixture() {
+        let provider = FsProvider::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/__fixtures__/deno"
+        ))
+        .unwrap();
+        let registry = RuleRegistry::with_defaults().unwrap();
+
+        let files = provider.list_dir(&provider.base_path());
+        let result = detect_deno_component(&files, &provider, &registry).unwrap();
+
+        assert_eq!(result.name, "deno");
+        assert_eq!(
+            result.dependencies,
+            vec![dep("deno", "deno.land/x/postgres", "v0.17.0", "v0.17.0")]
+        );
+        // Matched with the URL rule /x/postgres@/
+        assert!(result.techs.contains("postgresql"));
+    }
+
+    #[test]
+    fn test_parse_url() {
+        assert_eq!(
+            parse_url("https://esm.sh/@preact/signals@1.2.0?target=deno"),
+            ("esm.sh/@preact/signals".to_string(), "1.2.0".to_string())
+        );
+        assert_eq!(
+            parse_url("https://esm.sh/react"),
+            ("esm.sh/react".to_string(), "latest".to_string())
+        );
+        assert_eq!(
+            split_package("@std/path/join"),
+            ("@std/path".to_string(), "latest".to_string())
+        );
+    }
+}
@@ -0,0 +1,31 @@
+pub mod component;
+use component::detect_deno_component;
+
+use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
+use crate::types::rule::{Rule, RuleDependency, RuleFiles};
+
+pub fn register_deno(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("deno"),
+            name: String::from("Deno"),
+            r#type: String::from("language"),
+            files: Some(RuleFiles::FilesArray {
+                files: vec![
+                    String::from("deno.json"),
+                    String::from("deno.jsonc"),
+                    String::from("deno.lock"),
+                ],
+            }),
+            dependencies: Some(vec![RuleDependency {
+                r#type: String::from("docker"),
+                name: Some(String::from("denoland/deno")),
+                example: Some(String::from("denoland/deno:1.38.0")),
+            }]),
+            detect: Some(vec![detect_deno_component]),
+            ..Default::default()
+        },
+    )
+}
@@ -0,0 +1,411 @@
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::{
+    payload::payload::{dependency, Payload},
+    provider::base::{BaseProvider, FileType, ProviderFile},
+    rules::{match_dependencies::match_dependencies, registry::RuleRegistry},
+};
+
+lazy_static::lazy_static! {
+    // Dockerfile, Dockerfile.prod, api.Dockerfile, ...
+    static ref FILES_REG: Regex = Regex::new(r"(?i)^(.+\.)?dockerfile(\..+)?$").unwrap();
+    static ref HEREDOC_REG: Regex =
+        Regex::new(r#"<<-?["']?([A-Za-z_][A-Za-z0-9_]*)["']?"#).unwrap();
+    // $NAME, ${NAME} or ${NAME:-word}
+    static ref VAR_REG: Regex = Regex::new(concat!(
+        r"\$(?:\{([A-Za-z_][A-Za-z0-9_]*)(?::?([-+])([^}]*))?\}",
+        r"|([A-Za-z_][A-Za-z0-9_]*))"
+    ))
+    .unwrap();
+}
+
+/// Images pulled by a Dockerfile, in order of appearance.
+/// Stages are followed so that `FROM builder` or `COPY --from=builder` are not reported.
+pub fn parse_dockerfile(content: &str) -> Vec<String> {
+    let mut images: Vec<String> = Vec::new();
+    let mut stages: Vec<String> = Vec::new();
+    let mut args: HashMap<String, String> = HashMap::new();
+
+    for (instruction, value) in instructions(content) {
+        match instruction.as_str() {
+            "ARG" => {
+                for (name, default) in parse_args(&value) {
+                    let default = default.and_then(|d| substitute(&d, &args));
+                    match default {
+                        Some(default) => args.insert(name, default),
+                        // Declared without a default, the value is only known at build time
+                        None => args.remove(&name),
+                    };
+                }
+            }
+            "FROM" => {
+                let mut words = value.split_whitespace().filter(|w| !w.starts_with("--"));
+                let Some(image) = words.next() else {
+                    continue;
+                };
+                if let Some(image) = substitute(image, &args) {
+                    add_image(&mut images, &stages, image);
+                }
+
+                if let (Some(keyword), Some(stage)) = (words.next(), words.next()) {
+                    if keyword.eq_ignore_ascii_case("as") {
+                        stages.push(stage.to_lowercase());
+                    }
+                }
+            }
+            "COPY" => {
+                let from = value
+                    .split_whitespace()
+                    .take_while(|w| w.starts_with("--"))
+                    .find_map(|w| w.strip_prefix("--from="));
+                if let Some(image) = from.and_then(|from| substitute(from, &args)) {
+                    // Stages can also be referenced by index
+                    if image.parse::<usize>().is_err() {
+                        add_image(&mut images, &stages, image);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    images
+}
+
+fn add_image(images: &mut Vec<String>, stages: &[String], image: String) {
+    if image.eq_ignore_ascii_case("scratch")
+        || stages.contains(&image.to_lowercase())
+        || images.contains(&image)
+    {
+        return;
+    }
+    images.push(image);
+}
+
+/// Instructions with their arguments, continuation lines joined and comments removed.
+fn instructions(content: &str) -> Vec<(String, String)> {
+    let mut escape = '\\';
+    let mut list: Vec<(String, String)> = Vec::new();
+    let mut current = String::new();
+    let mut heredoc: Option<String> = None;
+    let mut directives = true;
+
+    for line in content.lines() {
+        if let Some(delimiter) = &heredoc {
+            if line.trim() == delimiter {
+                heredoc = None;
+            }
+            continue;
+        }
+
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            // Parser directives are only allowed at the very top
+            if directives {
+                if let Some(value) = trimmed.strip_prefix("# escape=") {
+                    escape = value.trim().chars().next().unwrap_or(escape);
+                }
+            }
+            continue;
+        }
+        directives = false;
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        match trimmed.strip_suffix(escape) {
+            Some(part) => {
+                current.push_str(part);
+                current.push(' ');
+            }
+            None => {
+                current.push_str(trimmed);
+                if let Some(captures) = HEREDOC_REG.captures(&current) {
+                    heredoc = Some(captures[1].to_string());
+                }
+
+                let instruction = std::mem::take(&mut current);
+                let (name, value) =
+                    instruction.split_once(char::is_whitespace).unwrap_or((&instruction, ""));
+                list.push((name.to_uppercase(), value.trim().to_string()));
+            }
+        }
+    }
+
+    list
+}
+
+/// `ARG NAME`, `ARG NAME=value` or many at once: `ARG A=1 B="2"`
+fn parse_args(value: &str) -> Vec<(String, Option<String>)> {
+    value
+        .split_whitespace()
+        .map(|arg| match arg.split_once('=') {
+            Some((name, default)) => (name.to_string(), Some(unquote(default).to_string())),
+            None => (arg.to_string(), None),
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
+/// Replace `$NAME`, `${NAME}`, `${NAME:-default}` and `${NAME:+alternative}`.
+/// Returns `None` when a variable can't be resolved.
+fn substitute(value: &str, args: &HashMap<String, String>) -> Option<String> {
+    let mut resolved = true;
+    let result = VAR_REG.replace_all(value, |captures: &regex::Captures| {
+        let name = captures.get(1).or(captures.get(4)).unwrap().as_str();
+        let current = args.get(name).filter(|v| !v.is_empty());
+        let word = captures.get(3).map_or("", |m| m.as_str());
+
+        match (captures.get(2).map(|m| m.as_str()), current) {
+            (Some("-"), None) => word.to_string(),
+            (Some("+"), Some(_)) => word.to_string(),
+            (Some("+"), None) => String::new(),
+            (_, Some(current)) => current.clone(),
+            (_, None) => {
+                resolved = false;
+                String::new()
+            }
+        }
+    });
+
+    if !resolved || result.is_empty() {
+        return None;
+    }
+    Some(result.to_string())
+}
+
+/// Split an image reference into a `docker` dependency, e.g: `ghcr.io:443/acme/tool:1.2`
+///
+/// Only a digest pins the exact image, a tag can be moved.
+pub fn image_dependency(image: &str) -> Option<Vec<String>> {
+    let image = image.trim();
+    // Variables or expressions are only known at runtime
+    if image.is_empty() || image.contains('$') {
+        return None;
+    }
+
+    let (name, tag) = split_image(image);
+    let digest = image.split_once('@').map_or("", |(_, digest)| digest);
+    Some(dependency(
+        "docker",
+        name,
+        digest,
+        tag.unwrap_or("latest"),
+        "",
+    ))
+}
+
+/// Name and tag (or digest) of an image, e.g: `localhost:5000/api` and `dev`
+pub(crate) fn split_image(image: &str) -> (&str, Option<&str>) {
+    let (image, digest) = match image.split_once('@') {
+        Some((image, digest)) => (image, Some(digest)),
+        None => (image, None),
+    };
+    // A colon before the last slash is a registry port, not a tag
+    let name_start = image.rfind('/').map_or(0, |idx| idx + 1);
+    match image[name_start..].rfind(':') {
+        Some(idx) => (
+            &image[..name_start + idx],
+            Some(&image[name_start + idx + 1..]),
+        ),
+        None => (image, digest),
+    }
+}
+
+/// Official images can be written with their full name: `docker.io/library/node`
+pub(crate) fn image_names(name: &str) -> Vec<String> {
+    let mut names = vec![name.to_string()];
+    let short = name.strip_prefix("docker.io/").unwrap_or(name);
+    let short = short.strip_prefix("library/").unwrap_or(short);
+    if short != name {
+        names.push(short.to_string());
+    }
+    names
+}
+
+pub fn detect_dockerfile_component(
+    files: &Vec<ProviderFile>,
+    provider: &dyn BaseProvider,
+    registry: &RuleRegistry,
+) -> Result<Payload, bool> {
+    let mut pl: Option<Payload> = None;
+    let mut techs: HashMap<String, Vec<String>> = HashMap::new();
+
+    for file in files {
+        if file.file_type != FileType::File || !FILES_REG.is_match(&file.name) {
+            continue;
+        }
+
+        let Some(content) = provider.open(&file.fp) else {
+            continue;
+        };
+
+        // The base images belong to the component of this folder
+        let pl = pl.get_or_insert_with(|| Payload::new("virtual", &file.fp));
+        pl.path.insert(file.fp.clone());
+
+        for image in parse_dockerfile(&content) {
+            let Some(dep) = image_dependency(&image) else {
+                continue;
+            };
+            if pl.dependencies.contains(&dep) {
+                continue;
+            }
+
+            for (tech, reasons) in match_dependencies(&image_names(&dep[1]), "docker", registry) {
+                techs.entry(tech).or_default().extend(reasons);
+            }
+            pl.dependencies.push(dep);
+        }
+    }
+
+    let Some(mut pl) = pl else {
+        return Err(false);
+    };
+    pl.add_techs(&techs, registry);
+
+    Ok(pl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::fake::FakeProvider;
+
+    fn dep(name: &str, resolved: &str, declared: &str) -> Vec<String> {
+        dependency("docker", name, resolved, declared, "")
+    }
+
+    #[test]
+    fn test_parse_dockerfile() {
+        let content = r#"
+# syntax=docker/dockerfile:1
+ARG NODE_VERSION=18.17.0
+ARG REGISTRY
+ARG BASE="alpine"
+
+FROM --platform=$BUILDPLATFORM node:${NODE_VERSION}-${BASE} AS deps
+RUN npm ci
+
+from rust:1.71 as Builder
+ARG FEATURES
+RUN cargo build --release \
+    --features ${FEATURES}
+RUN <<EOF
+FROM ignored:1
+EOF
+
+FROM ${REGISTRY}/internal:1
+FROM ${REGISTRY:-ghcr.io}/acme/base@sha256:abc
+
+FROM builder AS test
+FROM scratch
+COPY --from=deps /app/node_modules ./node_modules
+COPY --from=0 /bin/a /bin/a
+COPY --chown=1000 --from=BUILDER /target/release/app /app
+COPY --from=gcr.io/distroless/cc:nonroot /lib /lib
+"#;
+
+        assert_eq!(
+            parse_dockerfile(content),
+            vec![
+                "node:18.17.0-alpine",
+                "rust:1.71",
+                "ghcr.io/acme/base@sha256:abc",
+                "gcr.io/distroless/cc:nonroot",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_dockerfile_escape() {
+        let content = "# escape=`\nFROM `\n  mcr.microsoft.com/windows/servercore:ltsc2022\n";
+        assert_eq!(
+            parse_dockerfile(content),
+            vec!["mcr.microsoft.com/windows/servercore:ltsc2022"]
+        );
+    }
+
+    #[test]
+    fn test_image_dependency() {
+        assert_eq!(image_dependency("node"), Some(dep("node", "", "latest")));
+        assert_eq!(
+            image_dependency("node:18-alpine"),
+            Some(dep("node", "", "18-alpine"))
+        );
+        assert_eq!(
+            image_dependency("localhost:5000/app"),
+            Some(dep("localhost:5000/app", "", "latest"))
+        );
+        assert_eq!(
+            image_dependency("node:18@sha256:abc"),
+            Some(dep("node", "sha256:abc", "18"))
+        );
+        assert_eq!(
+            image_dependency("node@sha256:abc"),
+            Some(dep("node", "sha256:abc", "sha256:abc"))
+        );
+        assert_eq!(image_dependency("${{ matrix.image }}"), None);
+    }
+
+    #[test]
+    fn test_detect_dockerfile_component() {
+        let dockerfile = "FROM docker.io/library/rust:1.71 AS build\nFROM debian:bookworm-slim\n";
+        let dockerfile_dev = "FROM cimg/node:18.17\n";
+
+        let provider = FakeProvider::new(
+            HashMap::from_iter([(
+                "/api".to_string(),
+                vec![
+                    "Dockerfile".to_string(),
+                    "dev.Dockerfile".to_string(),
+                    "Dockerfile.d/".to_string(),
+                ],
+            )]),
+            HashMap::from_iter([
+                ("/api/Dockerfile".to_string(), dockerfile.to_string()),
+                (
+                    "/api/dev.Dockerfile".to_string(),
+                    dockerfile_dev.to_string(),
+                ),
+            ]),
+        );
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let result =
+            detect_dockerfile_component(&provider.list_dir("/api"), &provider, &registry).unwrap();
+
+        assert_eq!(result.name, "virtual");
+        assert_eq!(result.path.len(), 2);
+        assert_eq!(
+            result.dependencies,
+            vec![
+                dep("docker.io/library/rust", "", "1.71"),
+                dep("debian", "", "bookworm-slim"),
+                dep("cimg/node", "", "18.17"),
+            ]
+        );
+        assert!(result.techs.contains("rust"));
+        assert!(result.techs.contains("nodejs"));
+    }
+
+    #[test]
+    fn test_detect_dockerfile_nothing() {
+        let provider = FakeProvider::new(
+            HashMap::from_iter([("/".to_string(), vec!["docker-compose.yml".to_string()])]),
+            HashMap::new(),
+        );
+
+        let res =
+            detect_dockerfile_component(&provider.list_dir("/"), &provider, &RuleRegistry::new());
+        assert!(res.is_err());
+    }
+}
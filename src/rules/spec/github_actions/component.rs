@@ -0,0 +1,314 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::{
+    payload::payload::{dependency, Payload},
+    provider::base::{BaseProvider, FileType, ProviderFile},
+    rules::{
+        match_dependencies::match_dependencies,
+        registry::RuleRegistry,
+        spec::docker::dockerfile::{image_dependency, image_names},
+    },
+};
+
+const FOLDER: &str = ".github";
+const WORKFLOWS: &str = "workflows";
+
+#[derive(Debug, Deserialize)]
+struct Workflow {
+    jobs: Option<BTreeMap<String, Job>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Job {
+    /// Reusable workflow
+    uses: Option<String>,
+    container: Option<Container>,
+    services: Option<BTreeMap<String, Container>>,
+    steps: Option<Vec<Step>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Container {
+    Image(String),
+    Detailed { image: Option<String> },
+}
+
+#[derive(Debug, Deserialize)]
+struct Step {
+    uses: Option<String>,
+}
+
+pub fn detect_github_actions_component(
+    files: &Vec<ProviderFile>,
+    provider: &dyn BaseProvider,
+    registry: &RuleRegistry,
+) -> Result<Payload, bool> {
+    for file in files {
+        if file.name != FOLDER || file.file_type != FileType::Dir {
+            continue;
+        }
+
+        let folder = Path::new(&file.fp).join(WORKFLOWS);
+        let workflows: Vec<ProviderFile> = provider
+            .list_dir(&folder.to_string_lossy())
+            .into_iter()
+            .filter(|f| {
+                f.file_type == FileType::File
+                    && (f.name.ends_with(".yml") || f.name.ends_with(".yaml"))
+            })
+            .collect();
+        if workflows.is_empty() {
+            continue;
+        }
+
+        let mut deps: BTreeSet<Vec<String>> = BTreeSet::new();
+        let mut paths: Vec<String> = Vec::new();
+        for workflow in &workflows {
+            let Some(content) = provider.open(&workflow.fp) else {
+                continue;
+            };
+
+            // Workflows are often generated from templates, one bad file should not hide the others
+            let parsed: Workflow = match serde_yaml::from_str(&content) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    log::warn!("Failed to parse GitHub workflow: {} - {}", workflow.fp, e);
+                    continue;
+                }
+            };
+
+            paths.push(workflow.fp.clone());
+            for job in parsed.jobs.unwrap_or_default().into_values() {
+                read_job(job, &mut deps);
+            }
+        }
+
+        let Some(first) = paths.first() else {
+            continue;
+        };
+
+        let mut ci = Payload::new("GitHub Actions", first);
+        ci.path.extend(paths.iter().cloned());
+        ci.tech = Some("github.actions".to_string());
+
+        let mut techs: HashMap<String, Vec<String>> = HashMap::new();
+        for dep in &deps {
+            let names = match dep[0].as_str() {
+                "githubAction" => action_names(&dep[1]),
+                "docker" => image_names(&dep[1]),
+                _ => vec![dep[1].clone()],
+            };
+            for (tech, reasons) in match_dependencies(&names, &dep[0], registry) {
+                techs.entry(tech).or_default().extend(reasons);
+            }
+        }
+        ci.add_techs(&techs, registry);
+        ci.dependencies = deps.into_iter().collect();
+
+        // The CI is a component of its own, not the context of the folder it was found in
+        let mut pl = Payload::new("virtual", first);
+        pl.add_child(ci);
+
+        return Ok(pl);
+    }
+
+    Err(false)
+}
+
+fn read_job(job: Job, deps: &mut BTreeSet<Vec<String>>) {
+    let uses = job
+        .uses
+        .into_iter()
+        .chain(job.steps.unwrap_or_default().into_iter().filter_map(|step| step.uses));
+    for action in uses {
+        if let Some(dep) = action_dependency(&action) {
+            deps.insert(dep);
+        }
+    }
+
+    let containers =
+        job.container.into_iter().chain(job.services.unwrap_or_default().into_values());
+    for container in containers {
+        let image = match container {
+            Container::Image(image) => Some(image),
+            Container::Detailed { image } => image,
+        };
+        if let Some(dep) = image.as_deref().and_then(image_dependency) {
+            deps.insert(dep);
+        }
+    }
+}
+
+/// Dependency for a `uses:`, e.g: `actions/checkout@v3` or `docker://alpine:3.8`
+fn action_dependency(action: &str) -> Option<Vec<String>> {
+    if let Some(image) = action.strip_prefix("docker://") {
+        return image_dependency(image);
+    }
+    // Local action, part of this repository
+    if action.starts_with("./") {
+        return None;
+    }
+
+    let (name, reference) = action.split_once('@').unwrap_or((action, "latest"));
+    Some(dependency("githubAction", name, "", reference, ""))
+}
+
+/// An action can live in a sub folder of its repository (`github/codeql-action/init`),
+/// rules can target either.
+fn action_names(name: &str) -> Vec<String> {
+    let mut names = vec![name.to_string()];
+    let repository: Vec<&str> = name.splitn(3, '/').take(2).collect();
+    if repository.len() == 2 && repository.join("/") != name {
+        names.push(repository.join("/"));
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{fake::FakeProvider, fs::FsProvider};
+
+    fn dep(r#type: &str, name: &str, declared: &str) -> Vec<String> {
+        dependency(r#type, name, "", declared, "")
+    }
+
+    #[test]
+    fn test_detect_github_actions_component() {
+        let workflow = r#"
+name: Deploy
+on: [push]
+jobs:
+  test:
+    runs-on: ubuntu-latest
+    container:
+      image: node:18-alpine
+    services:
+      db:
+        image: docker.io/library/postgres:14.5
+      cache:
+        image: ${{ matrix.redis }}
+    steps:
+      - uses: actions/checkout@v4
+      - uses: github/codeql-action/init@v2
+      - uses: ./.github/actions/setup
+      - uses: docker://ghcr.io:443/acme/tool:1.2
+      - run: npm test
+  publish:
+    steps:
+      - uses: docker/login-action@v2
+  reuse:
+    uses: acme/workflows/.github/workflows/deploy.yml@main
+"#;
+
+        let provider = FakeProvider::new(
+            HashMap::from_iter([
+                ("/".to_string(), vec![".github/".to_string()]),
+                ("/.github".to_string(), vec!["workflows/".to_string()]),
+                (
+                    "/.github/workflows".to_string(),
+                    vec![
+                        "deploy.yml".to_string(),
+                        "broken.yaml".to_string(),
+                        "README.md".to_string(),
+                    ],
+                ),
+            ]),
+            HashMap::from_iter([
+                (
+                    "/.github/workflows/deploy.yml".to_string(),
+                    workflow.to_string(),
+                ),
+                (
+                    "/.github/workflows/broken.yaml".to_string(),
+                    "jobs: [:".to_string(),
+                ),
+            ]),
+        );
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let result =
+            detect_github_actions_component(&provider.list_dir("/"), &provider, &registry).unwrap();
+
+        assert_eq!(result.name, "virtual");
+        assert_eq!(result.childs.len(), 1);
+
+        let ci = &result.childs[0];
+        assert_eq!(ci.name, "GitHub Actions");
+        assert_eq!(ci.tech.as_deref(), Some("github.actions"));
+        assert_eq!(ci.path.len(), 1);
+        assert_eq!(
+            ci.dependencies,
+            vec![
+                dep("docker", "docker.io/library/postgres", "14.5"),
+                dep("docker", "ghcr.io:443/acme/tool", "1.2"),
+                dep("docker", "node", "18-alpine"),
+                dep(
+                    "githubAction",
+                    "acme/workflows/.github/workflows/deploy.yml",
+                    "main"
+                ),
+                dep("githubAction", "actions/checkout", "v4"),
+                dep("githubAction", "docker/login-action", "v2"),
+                dep("githubAction", "github/codeql-action/init", "v2"),
+            ]
+        );
+        assert!(ci.techs.contains("docker"));
+        assert!(ci.techs.contains("nodejs"));
+        // Official images are matched by their short name
+        assert!(ci.techs.contains("postgresql"));
+    }
+
+    #[test]
+    fn test_detect_github_actions_fixture() {
+        let provider =
+            FsProvider::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/__fixtures__")).unwrap();
+        let registry = RuleRegistry::with_defaults().unwrap();
+
+        let files = provider.list_dir(&provider.base_path());
+        let result = detect_github_actions_component(&files, &provider, &registry).unwrap();
+
+        // invalid.yml is skipped
+        let ci = &result.childs[0];
+        assert_eq!(ci.path.len(), 1);
+        assert_eq!(
+            ci.dependencies,
+            vec![
+                dep("docker", "node", "18.17.0"),
+                dep("githubAction", "actions/checkout", "v3"),
+                dep("githubAction", "actions/setup-node", "v3"),
+                dep("githubAction", "codecov/codecov-action", "v3"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_github_actions_nothing() {
+        let provider = FakeProvider::new(
+            HashMap::from_iter([
+                ("/".to_string(), vec![".github/".to_string()]),
+                ("/.github".to_string(), vec!["CODEOWNERS".to_string()]),
+            ]),
+            HashMap::new(),
+        );
+
+        let res = detect_github_actions_component(
+            &provider.list_dir("/"),
+            &provider,
+            &RuleRegistry::new(),
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_action_names() {
+        assert_eq!(action_names("actions/checkout"), vec!["actions/checkout"]);
+        assert_eq!(
+            action_names("github/codeql-action/init"),
+            vec!["github/codeql-action/init", "github/codeql-action"]
+        );
+    }
+}
@@ -0,0 +1,26 @@
+pub mod component;
+use component::detect_github_actions_component;
+
+use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
+use regex::Regex;
+
+use crate::types::rule::{Rule, RuleFiles};
+
+pub fn register_github_actions(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("github.actions"),
+            name: String::from("GitHub Actions"),
+            r#type: String::from("ci"),
+            files: Some(RuleFiles::MatchFullPath {
+                match_full_path: true,
+                files: Regex::new(r"^\.github/workflows/[^/]+\.ya?ml$").unwrap(),
+                example: String::from(".github/workflows/ci.yml"),
+            }),
+            detect: Some(vec![detect_github_actions_component]),
+            ..Default::default()
+        },
+    )
+}
@@ -0,0 +1,380 @@
+use std::collections::HashSet;
+
+use crate::{
+    payload::payload::{dependency, Payload},
+    provider::base::{BaseProvider, ProviderFile},
+    rules::{match_dependencies::match_dependencies, registry::RuleRegistry},
+};
+
+const FILES: [&str; 1] = ["go.mod"];
+const LOCKFILE: &str = "go.sum";
+
+#[derive(Debug, Default)]
+struct GoMod {
+    module: Option<String>,
+    go: Option<String>,
+    require: Vec<GoRequire>,
+    replace: Vec<GoReplace>,
+    exclude: Vec<(String, String)>,
+}
+
+#[derive(Debug)]
+struct GoRequire {
+    path: String,
+    version: String,
+    indirect: bool,
+}
+
+#[derive(Debug)]
+struct GoReplace {
+    old_path: String,
+    old_version: Option<String>,
+    new_path: String,
+    new_version: Option<String>,
+}
+
+pub fn detect_golang_component(
+    files: &Vec<ProviderFile>,
+    provider: &dyn BaseProvider,
+    registry: &RuleRegistry,
+) -> Result<Payload, bool> {
+    for file in files {
+        if !FILES.contains(&file.name.as_str()) {
+            continue;
+        }
+
+        let content = match provider.open(&file.fp) {
+            Some(content) => content,
+            None => continue,
+        };
+
+        let go_mod = parse_go_mod(&content);
+        let name = match go_mod.module {
+            Some(ref name) => name.clone(),
+            None => {
+                log::warn!("Failed to parse go.mod - no module: {}", file.fp);
+                continue;
+            }
+        };
+
+        let checksums = files
+            .iter()
+            .find(|f| f.name == LOCKFILE)
+            .and_then(|f| provider.open(&f.fp))
+            .map(|content| parse_go_sum(&content));
+
+        let mut pl = Payload::new(&name, &file.fp);
+
+        let mut deps_flatten: Vec<Vec<String>> = Vec::new();
+        let mut direct: Vec<String> = Vec::new();
+        for req in &go_mod.require {
+            if go_mod.exclude.iter().any(|(p, v)| p == &req.path && v == &req.version) {
+                continue;
+            }
+
+            let version = resolve_version(req, &go_mod.replace, checksums.as_ref());
+            if !req.indirect {
+                direct.push(req.path.clone());
+            }
+            // Indirect requirements are only there for the dependencies of the module
+            let scope = if req.indirect { "transitive" } else { "" };
+            deps_flatten.push(dependency(
+                "golang",
+                &req.path,
+                &version,
+                &req.version,
+                scope,
+            ));
+        }
+
+        if let Some(go) = go_mod.go {
+            pl.reason.insert(format!("go: {}", go));
+        }
+
+        // Indirect requirements are listed for completeness, they don't say what the module uses
+        let techs = match_dependencies(&direct, "golang", registry);
+
+        pl.add_techs(&techs, registry);
+        pl.dependencies = deps_flatten;
+
+        return Ok(pl);
+    }
+
+    Err(false)
+}
+
+fn parse_go_mod(content: &str) -> GoMod {
+    let mut go_mod = GoMod::default();
+    let mut block: Option<String> = None;
+
+    for line in content.lines() {
+        // A comment starts a token, a path can contain `//`
+        let comment_start = line
+            .match_indices("//")
+            .map(|(idx, _)| idx)
+            .find(|idx| line[..*idx].chars().last().is_none_or(char::is_whitespace));
+        let (code, comment) = match comment_start {
+            Some(idx) => (&line[..idx], &line[idx + 2..]),
+            None => (line, ""),
+        };
+        let indirect = comment.trim().starts_with("indirect");
+        let tokens: Vec<String> = code
+            .split_whitespace()
+            .map(|t| t.trim_matches(|c| c == '"' || c == '`').to_string())
+            .collect();
+        if tokens.is_empty() {
+            continue;
+        }
+
+        if let Some(ref directive) = block {
+            if tokens[0] == ")" {
+                block = None;
+            } else {
+                apply_directive(&mut go_mod, directive, &tokens, indirect);
+            }
+            continue;
+        }
+
+        if tokens.len() == 2 && tokens[1] == "(" {
+            block = Some(tokens[0].clone());
+            continue;
+        }
+
+        apply_directive(&mut go_mod, &tokens[0], &tokens[1..], indirect);
+    }
+
+    go_mod
+}
+
+fn apply_directive(go_mod: &mut GoMod, directive: &str, args: &[String], indirect: bool) {
+    match (directive, args) {
+        ("module", [path, ..]) => go_mod.module = Some(path.clone()),
+        ("go", [version, ..]) => go_mod.go = Some(version.clone()),
+        ("require", [path, version, ..]) => go_mod.require.push(GoRequire {
+            path: path.clone(),
+            version: version.clone(),
+            indirect,
+        }),
+        ("exclude", [path, version, ..]) => go_mod.exclude.push((path.clone(), version.clone())),
+        ("replace", _) => {
+            let Some(idx) = args.iter().position(|t| t == "=>") else {
+                return;
+            };
+            let (old, new) = (&args[..idx], &args[idx + 1..]);
+            if old.is_empty() || new.is_empty() {
+                return;
+            }
+            go_mod.replace.push(GoReplace {
+                old_path: old[0].clone(),
+                old_version: old.get(1).cloned(),
+                new_path: new[0].clone(),
+                new_version: new.get(1).cloned(),
+            });
+        }
+        _ => {}
+    }
+}
+
+/// Module versions checksummed in go.sum, for their source or only their go.mod.
+fn parse_go_sum(content: &str) -> HashSet<(String, String)> {
+    let mut checksums = HashSet::new();
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let [path, version, _hash] = parts[..] else {
+            continue;
+        };
+        let version = version.strip_suffix("/go.mod").unwrap_or(version);
+        checksums.insert((path.to_string(), version.to_string()));
+    }
+    checksums
+}
+
+fn resolve_version(
+    req: &GoRequire,
+    replace: &[GoReplace],
+    checksums: Option<&HashSet<(String, String)>>,
+) -> String {
+    let replaced = replace.iter().find(|r| {
+        r.old_path == req.path && r.old_version.as_ref().is_none_or(|v| v == &req.version)
+    });
+    if let Some(r) = replaced {
+        if r.new_path.starts_with("./")
+            || r.new_path.starts_with("../")
+            || r.new_path.starts_with('/')
+        {
+            return format!("path:{}", r.new_path);
+        }
+        let version = r.new_version.clone().unwrap_or_else(|| req.version.clone());
+        if r.new_path == req.path {
+            return version;
+        }
+        return format!("{}@{}", r.new_path, version);
+    }
+
+    // go.mod lists the version selected for the build (MVS), go.sum only confirms it:
+    // older versions it holds are the ones required by other modules
+    match checksums {
+        Some(checksums) if !checksums.contains(&(req.path.clone(), req.version.clone())) => {
+            String::new()
+        }
+        _ => req.version.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{fake::FakeProvider, fs::FsProvider};
+    use crate::types::rule::{Rule, RuleDependency};
+    use std::collections::HashMap;
+
+    fn dep(name: &str, resolved: &str, declared: &str) -> Vec<String> {
+        dependency("golang", name, resolved, declared, "")
+    }
+
+    #[test]
+    fn test_detect_golang_component() {
+        let go_mod = r#"
+module github.com/acme/api
+
+go 1.21
+
+require github.com/lib/pq v1.10.0
+
+require (
+	github.com/google/uuid v1.3.0
+	golang.org/x/text v0.3.7 // indirect
+	github.com/acme/shared v0.0.0-00010101000000-000000000000
+	github.com/old/lib v1.0.0
+	github.com/broken/lib v1.1.0
+	github.com/mirrored/lib v1.0.0
+)
+
+replace github.com/acme/shared => ../shared
+
+replace (
+	github.com/old/lib v1.0.0 => github.com/new/lib v1.2.0
+	github.com/mirrored/lib => example.com//mirror/lib v2.0.0 // moved
+)
+
+exclude github.com/broken/lib v1.1.0
+"#;
+        let go_sum = r#"
+github.com/google/uuid v1.3.0/go.mod h1:TIyPZe4MgqvfeYDBFedMoGGpEw/LqOeaOT+nhxU+yHo=
+github.com/google/uuid v1.3.0 h1:t6JiXgmwXMjEs8VusXIJk2BXHsn+wx8BZdTaoZ5fu7I=
+github.com/google/uuid v1.3.1/go.mod h1:TIyPZe4MgqvfeYDBFedMoGGpEw/LqOeaOT+nhxU+yHo=
+github.com/lib/pq v1.9.0 h1:L8nSXQQzAYByakOFMTwpjRoHsMJklur4Gi59b6VivR8=
+github.com/lib/pq v1.10.0 h1:Zx5DJFEYQXio93kgXnQ09fXNiUKsqv4OUEu2UtGcB1E=
+"#;
+
+        let provider = FakeProvider::new(
+            HashMap::from_iter([(
+                "/".to_string(),
+                vec!["go.mod".to_string(), "go.sum".to_string()],
+            )]),
+            HashMap::from_iter([
+                ("/go.mod".to_string(), go_mod.to_string()),
+                ("/go.sum".to_string(), go_sum.to_string()),
+            ]),
+        );
+
+        let registry = RuleRegistry::from_rules(vec![
+            Rule {
+                tech: "postgresql".to_string(),
+                name: "Postgres".to_string(),
+                r#type: "db".to_string(),
+                dependencies: Some(vec![RuleDependency {
+                    r#type: "golang".to_string(),
+                    name: Some("github.com/lib/pq".to_string()),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            },
+            Rule {
+                tech: "sentry".to_string(),
+                name: "Sentry".to_string(),
+                r#type: "tool".to_string(),
+                dependencies: Some(vec![RuleDependency {
+                    r#type: "golang".to_string(),
+                    name: Some("golang.org/x/text".to_string()),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            },
+        ])
+        .unwrap();
+
+        let result =
+            detect_golang_component(&provider.list_dir("/"), &provider, &registry).unwrap();
+
+        assert_eq!(result.name, "github.com/acme/api");
+        assert!(result.path.contains("/go.mod"));
+
+        let deps = &result.dependencies;
+        assert_eq!(deps.len(), 6);
+        // go.mod holds the selected version, older ones in go.sum are not used
+        assert!(deps.contains(&dep("github.com/lib/pq", "v1.10.0", "v1.10.0")));
+        assert!(deps.contains(&dep("github.com/google/uuid", "v1.3.0", "v1.3.0")));
+        // Not confirmed by go.sum
+        assert!(deps.contains(&dependency(
+            "golang",
+            "golang.org/x/text",
+            "",
+            "v0.3.7",
+            "transitive"
+        )));
+        assert!(deps.contains(&dep(
+            "github.com/acme/shared",
+            "path:../shared",
+            "v0.0.0-00010101000000-000000000000"
+        )));
+        assert!(deps.contains(&dep(
+            "github.com/old/lib",
+            "github.com/new/lib@v1.2.0",
+            "v1.0.0"
+        )));
+        // `//` inside a path is not a comment
+        assert!(deps.contains(&dep(
+            "github.com/mirrored/lib",
+            "example.com//mirror/lib@v2.0.0",
+            "v1.0.0"
+        )));
+        assert!(result.reason.contains("go: 1.21"));
+
+        // Indirect dependencies are not matched
+        assert!(result.techs.contains("postgresql"));
+        assert!(!result.techs.contains("sentry"));
+        assert!(result.childs.iter().any(|c| c.tech.as_deref() == Some("postgresql")));
+    }
+
+    #[test]
+    fn test_detect_golang_component_fixture() {
+        let provider = FsProvider::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/__fixtures__/golang"
+        ))
+        .unwrap();
+        let registry = RuleRegistry::with_defaults().unwrap();
+
+        let files = provider.list_dir(&provider.base_path());
+        let result = detect_golang_component(&files, &provider, &registry).unwrap();
+
+        assert_eq!(result.name, "github.com/specfy/specfy");
+        assert_eq!(
+            result.dependencies,
+            vec![dep("github.com/google/uuid", "v1.3.0", "v1.3.0")]
+        );
+        assert!(result.reason.contains("go: 1.20"));
+    }
+
+    #[test]
+    fn test_detect_golang_component_invalid() {
+        let provider = FakeProvider::new(
+            HashMap::from_iter([("/".to_string(), vec!["go.mod".to_string()])]),
+            HashMap::from_iter([("/go.mod".to_string(), "go 1.20\n".to_string())]),
+        );
+
+        let res = detect_golang_component(&provider.list_dir("/"), &provider, &RuleRegistry::new());
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,39 @@
+pub mod component;
+use component::detect_golang_component;
+
+use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
+use crate::types::rule::{Rule, RuleDependency, RuleFiles};
+
+pub fn register_golang(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("golang"),
+            name: String::from("Golang"),
+            r#type: String::from("language"),
+            files: Some(RuleFiles::FilesArray {
+                files: vec![String::from("go.mod"), String::from("main.go")],
+            }),
+            dependencies: Some(vec![
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("golang")),
+                    example: Some(String::from("golang:1.21-alpine")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("circleci/golang")),
+                    example: Some(String::from("circleci/golang:1.17")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("cimg/go")),
+                    example: Some(String::from("cimg/go:1.21")),
+                },
+            ]),
+            detect: Some(vec![detect_golang_component]),
+            ..Default::default()
+        },
+    )
+}
@@ -1,3 +1,11 @@
+pub mod deno;
 pub mod docker;
+pub mod github_actions;
+pub mod golang;
+pub mod jsonc;
 pub mod nodejs;
+pub mod php;
+pub mod python;
+pub mod ruby;
 pub mod rust;
+pub mod terraform;
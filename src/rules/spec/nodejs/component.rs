@@ -1,10 +1,13 @@
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::path::Path;
 
+use super::lockfile::{read_lockfile, Lockfile, LOCKFILES};
+use super::workspace::{find_workspace, Workspace};
 use crate::{
-    payload::payload::Payload,
-    provider::base::{BaseProvider, ProviderFile},
-    rules::match_dependencies::match_dependencies,
+    payload::payload::{dependency, Payload},
+    provider::base::{normalize_path, relative_path, BaseProvider, FileType, ProviderFile},
+    rules::{match_dependencies::match_dependencies, registry::RuleRegistry},
 };
 
 const FILES: [&str; 1] = ["package.json"];
@@ -12,14 +15,19 @@ const FILES: [&str; 1] = ["package.json"];
 #[derive(Debug, Deserialize)]
 struct PackageJson {
     name: Option<String>,
-    dependencies: Option<HashMap<String, String>>,
+    dependencies: Option<BTreeMap<String, String>>,
     #[serde(rename = "devDependencies")]
-    dev_dependencies: Option<HashMap<String, String>>,
+    dev_dependencies: Option<BTreeMap<String, String>>,
 }
 
+/// Dependencies are resolved with the lockfile of the package, or the one of its workspace.
+/// Packages of the same workspace and local packages resolve to `path:<folder>`,
+/// and become edges between the packages of a monorepo.
+/// Transitive dependencies are only listed with `RuleRegistry::transitive`.
 pub fn detect_node_component(
     files: &Vec<ProviderFile>,
     provider: &dyn BaseProvider,
+    registry: &RuleRegistry,
 ) -> Result<Payload, bool> {
     for file in files {
         if !FILES.contains(&file.name.as_str()) {
@@ -34,39 +42,100 @@ pub fn detect_node_component(
         let package_json: PackageJson = match serde_json::from_str(&content) {
             Ok(manifest) => manifest,
             Err(e) => {
-                println!("Failed to parse package.json: {} - {}", file.fp, e);
+                log::warn!("Failed to parse package.json: {} - {}", file.fp, e);
                 continue;
             }
         };
 
+        // The root of a monorepo often has no name, only the packages matter
+        let workspace = find_workspace(&file.fp, provider);
+        let folder = normalize_path(Path::new(&file.fp).parent().unwrap_or(Path::new("")));
+        let is_root = workspace.as_ref().is_some_and(|workspace| workspace.root == folder);
         let name = match package_json.name {
             Some(name) => name,
+            None if is_root => "virtual".to_string(),
             None => continue,
         };
 
         let mut pl = Payload::new(&name, &file.fp);
-
-        // Collect all dependencies into a single HashMap
-        let mut deps = HashMap::new();
-        if let Some(ref deps_map) = package_json.dependencies {
-            deps.extend(deps_map.clone());
+        if let Some(workspace) = workspace.as_ref().filter(|_| is_root) {
+            for fp in &workspace.files {
+                let file_name = Path::new(fp).file_name().unwrap_or_default().to_string_lossy();
+                pl.path.insert(fp.clone());
+                pl.reason.insert(format!("monorepo: {}", file_name));
+            }
         }
-        if let Some(ref dev_deps) = package_json.dev_dependencies {
-            deps.extend(dev_deps.clone());
+
+        let own_lockfile = LOCKFILES.iter().find_map(|name| files.iter().find(|f| f.name == *name));
+        let lockfile = match own_lockfile {
+            Some(lockfile) => {
+                pl.path.insert(lockfile.fp.clone());
+                read_lockfile(lockfile, provider, ".")
+            }
+            None => workspace
+                .as_ref()
+                .and_then(|workspace| workspace_lockfile(workspace, &folder, provider)),
+        };
+
+        // Collect all dependencies, a dev dependency can't override a dependency
+        let mut deps: Vec<(String, String, &str)> = Vec::new();
+        let declared = package_json
+            .dependencies
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, range)| (name, range, ""))
+            .chain(
+                package_json
+                    .dev_dependencies
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(name, range)| (name, range, "dev")),
+            );
+        for (name, range, scope) in declared {
+            if !deps.iter().any(|(n, _, _)| *n == name) {
+                deps.push((name, range, scope));
+            }
         }
 
         // Match dependencies and create flattened dependency list
         let techs = match_dependencies(
-            &deps.keys().map(|s| s.to_string()).collect::<Vec<_>>(),
+            &deps.iter().map(|(name, _, _)| name.clone()).collect::<Vec<_>>(),
             "npm",
+            registry,
         );
 
-        let deps_flatten: Vec<Vec<String>> = deps
-            .into_iter()
-            .map(|(name, version)| vec!["npm".to_string(), name.to_string(), version])
+        let mut deps_flatten: Vec<Vec<String>> = deps
+            .iter()
+            .map(|(dep, range, scope)| {
+                let local = local_package(dep, range, &name, &folder, workspace.as_deref());
+                let resolved = match local {
+                    Some(path) => format!("path:{}", path),
+                    None => lockfile
+                        .as_ref()
+                        .and_then(|lock| lock.resolve(dep, range))
+                        .cloned()
+                        .unwrap_or_default(),
+                };
+                dependency("npm", dep, &resolved, range, scope)
+            })
             .collect();
 
-        pl.add_techs(&techs);
+        if let Some(lockfile) = lockfile.filter(|_| registry.transitive) {
+            // Only what this package installs, not every package of its workspace
+            let roots = deps_flatten
+                .iter()
+                .filter(|dep| !dep[2].is_empty() && !dep[2].starts_with("path:"))
+                .map(|dep| (dep[1].clone(), dep[2].clone()))
+                .collect();
+            for (name, version) in lockfile.dependencies_of(roots) {
+                if deps.iter().any(|(n, _, _)| *n == name) {
+                    continue;
+                }
+                deps_flatten.push(dependency("npm", &name, &version, "", "transitive"));
+            }
+        }
+
+        pl.add_techs(&techs, registry);
         pl.dependencies = deps_flatten;
 
         return Ok(pl);
@@ -75,10 +144,47 @@ pub fn detect_node_component(
     Err(false)
 }
 
+/// Lockfile at the root of the workspace, read for the member in `folder`
+fn workspace_lockfile(
+    workspace: &Workspace,
+    folder: &Path,
+    provider: &dyn BaseProvider,
+) -> Option<Lockfile> {
+    let member = relative_path(&workspace.root, folder).to_string_lossy().to_string();
+    let member = if member.is_empty() { "." } else { &member };
+
+    LOCKFILES.iter().find_map(|name| {
+        let file = ProviderFile {
+            name: name.to_string(),
+            fp: workspace.root.join(name).to_string_lossy().to_string(),
+            file_type: FileType::File,
+        };
+        read_lockfile(&file, provider, member)
+    })
+}
+
+/// Folder of a dependency that is not installed from a registry, relative to the package
+fn local_package(
+    dep: &str,
+    range: &str,
+    name: &str,
+    folder: &Path,
+    workspace: Option<&Workspace>,
+) -> Option<String> {
+    // `file:../lib` or `link:../lib`, the protocol can't be confused with a version
+    if let Some(path) = range.strip_prefix("file:").or_else(|| range.strip_prefix("link:")) {
+        return Some(path.to_string());
+    }
+
+    let target = workspace?.packages.get(dep).filter(|_| dep != name)?;
+    Some(relative_path(folder, target).to_string_lossy().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::provider::fake::FakeProvider;
+    use std::collections::HashMap;
 
     #[test]
     fn test_detect_node_component() {
@@ -112,33 +218,236 @@ mod tests {
         }];
 
         // Test the function
-        let result = detect_node_component(&files, &provider).unwrap();
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let result = detect_node_component(&files, &provider, &registry).unwrap();
 
         // Verify results
         assert_eq!(result.name, "test_project");
         assert!(result.path.contains("package.json"));
 
         // Verify dependencies are correctly parsed
-        let deps = result.dependencies;
-        assert!(deps.contains(&vec![
-            "npm".to_string(),
-            "express".to_string(),
-            "^4.17.1".to_string()
-        ]));
-        assert!(deps.contains(&vec![
-            "npm".to_string(),
-            "react".to_string(),
-            "17.0.2".to_string()
-        ]));
-        assert!(deps.contains(&vec![
-            "npm".to_string(),
-            "jest".to_string(),
-            "^27.0.0".to_string()
-        ]));
-        assert!(deps.contains(&vec![
-            "npm".to_string(),
-            "typescript".to_string(),
-            "4.5.4".to_string()
-        ]));
+        assert_eq!(
+            result.dependencies,
+            vec![
+                dependency("npm", "express", "", "^4.17.1", ""),
+                dependency("npm", "react", "", "17.0.2", ""),
+                dependency("npm", "jest", "", "^27.0.0", "dev"),
+                dependency("npm", "typescript", "", "4.5.4", "dev"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_node_component_lockfile() {
+        let package_content = r#"{
+    "name": "app",
+    "dependencies": { "express": "^4.17.1", "left-pad": "^1.0.0" },
+    "devDependencies": { "express": "^4.0.0", "pg": "^8.0.0" }
+}"#;
+        let lock_content = r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "name": "app" },
+    "node_modules/express": { "version": "4.18.2", "dependencies": { "debug": "2.6.9" } },
+    "node_modules/debug": { "version": "2.6.9" },
+    "node_modules/pg": { "version": "8.11.3", "dependencies": { "pg-types": "^2.1.0" } },
+    "node_modules/pg/node_modules/pg-types": { "version": "2.2.0" }
+  }
+}"#;
+
+        let provider = FakeProvider::new(
+            HashMap::from_iter([(
+                "/app".to_string(),
+                vec![
+                    "package.json".to_string(),
+                    "package-lock.json".to_string(),
+                    "yarn.lock".to_string(),
+                ],
+            )]),
+            HashMap::from_iter([
+                ("/app/package.json".to_string(), package_content.to_string()),
+                (
+                    "/app/package-lock.json".to_string(),
+                    lock_content.to_string(),
+                ),
+                ("/app/yarn.lock".to_string(), "invalid".to_string()),
+            ]),
+        );
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let result =
+            detect_node_component(&provider.list_dir("/app"), &provider, &registry).unwrap();
+
+        assert_eq!(result.path.len(), 2);
+        assert!(result.path.contains("/app/package-lock.json"));
+        assert_eq!(
+            result.dependencies,
+            vec![
+                dependency("npm", "express", "4.18.2", "^4.17.1", ""),
+                dependency("npm", "left-pad", "", "^1.0.0", ""),
+                dependency("npm", "pg", "8.11.3", "^8.0.0", "dev"),
+            ]
+        );
+        assert!(result.techs.contains("postgresql"));
+
+        let registry = RuleRegistry::with_defaults().unwrap().with_transitive(true);
+        let result =
+            detect_node_component(&provider.list_dir("/app"), &provider, &registry).unwrap();
+        assert_eq!(
+            result.dependencies[3..],
+            vec![
+                dependency("npm", "debug", "2.6.9", "", "transitive"),
+                dependency("npm", "pg-types", "2.2.0", "", "transitive"),
+            ]
+        );
+    }
+
+    const PNPM_LOCK: &str = r#"lockfileVersion: '9.0'
+
+importers:
+  .:
+    devDependencies:
+      turbo:
+        specifier: ^1.10.0
+        version: 1.10.0
+  packages/app:
+    dependencies:
+      '@acme/ui':
+        specifier: workspace:*
+        version: link:../ui
+      react:
+        specifier: ^18
+        version: 18.2.0
+"#;
+
+    #[test]
+    fn test_detect_node_component_workspace() {
+        let provider = FakeProvider::new(
+            HashMap::from_iter([
+                (
+                    "/".to_string(),
+                    vec![
+                        "package.json".to_string(),
+                        "pnpm-lock.yaml".to_string(),
+                        "pnpm-workspace.yaml".to_string(),
+                        "packages/".to_string(),
+                    ],
+                ),
+                (
+                    "/packages/".to_string(),
+                    vec!["app/".to_string(), "ui/".to_string()],
+                ),
+                (
+                    "/packages/app/".to_string(),
+                    vec!["package.json".to_string()],
+                ),
+            ]),
+            HashMap::from_iter([
+                (
+                    "/package.json".to_string(),
+                    r#"{ "private": true, "devDependencies": { "turbo": "^1.10.0" } }"#.to_string(),
+                ),
+                (
+                    "/pnpm-workspace.yaml".to_string(),
+                    "packages:\n  - 'packages/*'\n".to_string(),
+                ),
+                ("/pnpm-lock.yaml".to_string(), PNPM_LOCK.to_string()),
+                (
+                    "/packages/app/package.json".to_string(),
+                    r#"{
+    "name": "app",
+    "dependencies": { "@acme/ui": "workspace:*", "utils": "file:../../utils", "react": "^18" }
+}"#
+                    .to_string(),
+                ),
+                (
+                    "/packages/ui/package.json".to_string(),
+                    r#"{ "name": "@acme/ui" }"#.to_string(),
+                ),
+            ]),
+        );
+        let registry = RuleRegistry::with_defaults().unwrap();
+
+        // The root is only the monorepo
+        let root = detect_node_component(&provider.list_dir("/"), &provider, &registry).unwrap();
+        assert_eq!(root.name, "virtual");
+        assert!(root.path.contains("/pnpm-workspace.yaml"));
+        assert!(root.reason.contains("monorepo: pnpm-workspace.yaml"));
+
+        let app = detect_node_component(&provider.list_dir("/packages/app/"), &provider, &registry)
+            .unwrap();
+        assert_eq!(
+            app.dependencies,
+            vec![
+                dependency("npm", "@acme/ui", "path:../ui", "workspace:*", ""),
+                dependency("npm", "react", "18.2.0", "^18", ""),
+                dependency("npm", "utils", "path:../../utils", "file:../../utils", ""),
+            ]
+        );
+        assert!(app.reason.iter().all(|reason| !reason.starts_with("monorepo")));
+    }
+
+    #[test]
+    fn test_detect_node_component_workspace_lockfile() {
+        let lock = r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "workspaces": ["api", "web"] },
+    "api": { "name": "api", "version": "1.0.0" },
+    "node_modules/api": { "resolved": "api", "link": true },
+    "node_modules/express": { "version": "4.18.2", "dependencies": { "ms": "2.0.0" } },
+    "node_modules/ms": { "version": "2.0.0" },
+    "api/node_modules/debug": { "version": "4.3.4" },
+    "node_modules/debug": { "version": "2.6.9" },
+    "web": { "name": "web", "version": "1.0.0" },
+    "node_modules/web": { "resolved": "web", "link": true },
+    "node_modules/react": { "version": "18.2.0" }
+  }
+}"#;
+        let provider = FakeProvider::new(
+            HashMap::from_iter([
+                (
+                    "/".to_string(),
+                    vec![
+                        "api/".to_string(),
+                        "package-lock.json".to_string(),
+                        "package.json".to_string(),
+                    ],
+                ),
+                ("/api/".to_string(), vec!["package.json".to_string()]),
+            ]),
+            HashMap::from_iter([
+                (
+                    "/package.json".to_string(),
+                    r#"{ "workspaces": ["api"] }"#.to_string(),
+                ),
+                ("/package-lock.json".to_string(), lock.to_string()),
+                (
+                    "/api/package.json".to_string(),
+                    r#"{ "name": "api", "dependencies": { "express": "^4", "debug": "^4" } }"#
+                        .to_string(),
+                ),
+            ]),
+        );
+        let registry = RuleRegistry::with_defaults().unwrap();
+
+        // Only the root of the workspace has a lockfile
+        let api = detect_node_component(&provider.list_dir("/api/"), &provider, &registry).unwrap();
+        assert_eq!(
+            api.dependencies,
+            vec![
+                dependency("npm", "debug", "4.3.4", "^4", ""),
+                dependency("npm", "express", "4.18.2", "^4", ""),
+            ]
+        );
+        assert!(!api.path.contains("/package-lock.json"));
+
+        // The packages of the other members are not listed
+        let registry = registry.with_transitive(true);
+        let api = detect_node_component(&provider.list_dir("/api/"), &provider, &registry).unwrap();
+        assert_eq!(
+            api.dependencies[2..],
+            vec![dependency("npm", "ms", "2.0.0", "", "transitive")]
+        );
     }
 }
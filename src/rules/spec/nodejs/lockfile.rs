@@ -0,0 +1,784 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::{
+    provider::base::{BaseProvider, ProviderFile},
+    rules::spec::jsonc::strip_jsonc,
+};
+
+/// Supported lockfiles, by order of preference when a folder has many.
+pub const LOCKFILES: [&str; 5] = [
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lock",
+];
+
+/// Name and version (or range) of a package
+type Package = (String, String);
+
+/// Versions installed according to a lockfile.
+#[derive(Debug, Default)]
+pub struct Lockfile {
+    /// Version the package gets, by name, when the lockfile records it: hoisted in
+    /// node_modules (npm) or listed for each package of the workspace (pnpm, bun)
+    installed: HashMap<String, String>,
+    /// Version installed for a declared range, when the lockfile records it (yarn)
+    ranges: HashMap<Package, String>,
+    /// Every package installed, with the packages it depends on
+    packages: BTreeMap<Package, BTreeSet<Package>>,
+}
+
+impl Lockfile {
+    /// Version installed for a dependency declared in package.json.
+    pub fn resolve(&self, name: &str, range: &str) -> Option<&String> {
+        self.ranges
+            .get(&(name.to_string(), range.to_string()))
+            .or_else(|| self.installed.get(name))
+    }
+
+    /// Packages installed for the ones in `roots`, themselves included. A member of a
+    /// workspace only gets its own, not every package of the monorepo.
+    pub fn dependencies_of(&self, roots: Vec<Package>) -> BTreeSet<Package> {
+        let mut found = BTreeSet::new();
+        let mut queue = roots;
+        while let Some(package) = queue.pop() {
+            let Some(deps) = self.packages.get(&package) else {
+                continue;
+            };
+            if found.insert(package) {
+                queue.extend(deps.iter().filter(|dep| !found.contains(*dep)).cloned());
+            }
+        }
+        found
+    }
+
+    fn add(&mut self, name: &str, version: &str, top_level: bool) {
+        if top_level {
+            self.installed.entry(name.to_string()).or_insert_with(|| version.to_string());
+        }
+        self.packages.entry((name.to_string(), version.to_string())).or_default();
+    }
+
+    fn depend(&mut self, (name, version): (&str, &str), (dep, dep_version): (&str, &str)) {
+        self.packages
+            .entry((name.to_string(), version.to_string()))
+            .or_default()
+            .insert((dep.to_string(), dep_version.to_string()));
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PackageLock {
+    /// lockfileVersion 2 and 3
+    packages: Option<BTreeMap<String, PackageLockEntry>>,
+    /// lockfileVersion 1
+    dependencies: Option<BTreeMap<String, PackageLockV1Entry>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageLockEntry {
+    version: Option<String>,
+    #[serde(default)]
+    link: bool,
+    #[serde(default)]
+    dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    optional_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    peer_dependencies: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PackageLockV1Entry {
+    version: Option<String>,
+    #[serde(default)]
+    requires: BTreeMap<String, String>,
+    dependencies: Option<BTreeMap<String, PackageLockV1Entry>>,
+}
+
+/// Versions installed for a package, `member` is its folder relative to the lockfile: `.` for
+/// the package next to it, `packages/ui` for a member of a workspace.
+pub fn read_lockfile(
+    file: &ProviderFile,
+    provider: &dyn BaseProvider,
+    member: &str,
+) -> Option<Lockfile> {
+    let content = provider.open(&file.fp)?;
+
+    let lockfile = match file.name.as_str() {
+        "package-lock.json" | "npm-shrinkwrap.json" => read_package_lock(&content, member),
+        "yarn.lock" => Ok(read_yarn_lock(&content)),
+        "pnpm-lock.yaml" => read_pnpm_lock(&content, member),
+        "bun.lock" => read_bun_lock(&content, member),
+        _ => return None,
+    };
+
+    match lockfile {
+        Ok(lockfile) => Some(lockfile),
+        Err(e) => {
+            log::warn!("Failed to parse {}: {} - {}", file.name, file.fp, e);
+            None
+        }
+    }
+}
+
+fn read_package_lock(content: &str, member: &str) -> Result<Lockfile, String> {
+    let lock: PackageLock = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let mut lockfile = Lockfile::default();
+
+    if let Some(packages) = lock.packages {
+        // A member of a workspace gets its own copy when the hoisted one does not fit
+        let own = match member {
+            "." => "node_modules/".to_string(),
+            member => format!("{}/node_modules/", member),
+        };
+        let mut own_versions = Vec::new();
+        for (key, entry) in &packages {
+            // The root package, or a workspace folder
+            let Some(idx) = key.rfind("node_modules/") else {
+                continue;
+            };
+            let Some(version) = entry.version.as_ref().filter(|_| !entry.link) else {
+                continue;
+            };
+
+            let name = &key[idx + "node_modules/".len()..];
+            lockfile.add(name, version, *key == format!("node_modules/{}", name));
+            if *key == format!("{}{}", own, name) {
+                own_versions.push((name.to_string(), version.clone()));
+            }
+
+            let deps = entry
+                .dependencies
+                .keys()
+                .chain(entry.optional_dependencies.keys())
+                .chain(entry.peer_dependencies.keys());
+            for dep in deps {
+                if let Some(dep_version) = locate_package(&packages, key, dep) {
+                    lockfile.depend((name, version), (dep, dep_version));
+                }
+            }
+        }
+        lockfile.installed.extend(own_versions);
+        return Ok(lockfile);
+    }
+
+    add_package_lock_v1(
+        &mut lockfile,
+        &lock.dependencies.unwrap_or_default(),
+        &[],
+        true,
+    );
+    Ok(lockfile)
+}
+
+/// Version of `name` as node finds it from the package installed in `from`: in its own
+/// node_modules, then in the ones of its parents up to the root.
+fn locate_package<'a>(
+    packages: &'a BTreeMap<String, PackageLockEntry>,
+    from: &str,
+    name: &str,
+) -> Option<&'a String> {
+    let mut base = from;
+    loop {
+        let key = match base {
+            "" => format!("node_modules/{}", name),
+            base => format!("{}/node_modules/{}", base, name),
+        };
+        if let Some(entry) = packages.get(&key) {
+            // Linked to a package of the workspace, not installed
+            return entry.version.as_ref().filter(|_| !entry.link);
+        }
+        if base.is_empty() {
+            return None;
+        }
+        base = base.rfind("/node_modules/").map_or("", |idx| &base[..idx]);
+    }
+}
+
+/// lockfileVersion 1 nests the packages that could not be hoisted, a package is found in its
+/// own dependencies first, then in the ones of its parents (`scopes`).
+fn add_package_lock_v1<'a>(
+    lockfile: &mut Lockfile,
+    packages: &'a BTreeMap<String, PackageLockV1Entry>,
+    scopes: &[&'a BTreeMap<String, PackageLockV1Entry>],
+    top: bool,
+) {
+    let mut scopes = scopes.to_vec();
+    scopes.push(packages);
+
+    for (name, entry) in packages {
+        let Some(version) = &entry.version else {
+            continue;
+        };
+        lockfile.add(name, version, top);
+
+        for dep in entry.requires.keys() {
+            let dep_version = entry
+                .dependencies
+                .iter()
+                .chain(scopes.iter().rev().copied())
+                .find_map(|scope| scope.get(dep))
+                .and_then(|dep| dep.version.as_ref());
+            if let Some(dep_version) = dep_version {
+                lockfile.depend((name, version), (dep, dep_version));
+            }
+        }
+        if let Some(nested) = &entry.dependencies {
+            add_package_lock_v1(lockfile, nested, &scopes, false);
+        }
+    }
+}
+
+/// yarn.lock, both classic (v1) and berry (YAML like)
+///
+/// ```text
+/// "@babel/core@^7.0.0", "@babel/core@^7.1.0":    |  "react@npm:^18.0.0":
+///   version "7.1.2"                              |    version: 18.2.0
+/// ```
+fn read_yarn_lock(content: &str) -> Lockfile {
+    let mut lockfile = Lockfile::default();
+    // Specifiers of each entry, its version and its dependencies with their range
+    let mut entries: Vec<(Vec<Package>, String, Vec<Package>)> = Vec::new();
+    let mut in_dependencies = false;
+
+    for line in content.lines() {
+        if line.trim_start().starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+
+        if !line.starts_with(' ') {
+            let specifiers = line
+                .trim_end()
+                .trim_end_matches(':')
+                .split(',')
+                .filter_map(|spec| split_specifier(spec.trim().trim_matches('"')))
+                .collect();
+            entries.push((specifiers, String::new(), Vec::new()));
+            in_dependencies = false;
+            continue;
+        }
+        let Some((_, version, dependencies)) = entries.last_mut() else {
+            continue;
+        };
+
+        if !line.starts_with("   ") {
+            let field = line.trim();
+            in_dependencies = field == "dependencies:" || field == "optionalDependencies:";
+            // Only the entry's own version, not the ones of its dependencies
+            if let Some(value) = field.strip_prefix("version").filter(|v| v.starts_with([' ', ':']))
+            {
+                *version = value.trim_start_matches(':').trim().trim_matches('"').to_string();
+            }
+        } else if in_dependencies {
+            // `"@babel/highlight" "^7.10.4"` (classic) or `loose-envify: ^1.1.0` (berry)
+            let dep = line.trim();
+            let end = match dep.strip_prefix('"') {
+                Some(quoted) => quoted.find('"').map_or(dep.len(), |idx| idx + 2),
+                None => dep.find([':', ' ']).unwrap_or(dep.len()),
+            };
+            let range = dep[end..].trim_start_matches(':').trim().trim_matches('"');
+            let range = range.strip_prefix("npm:").unwrap_or(range);
+            dependencies.push((dep[..end].trim_matches('"').to_string(), range.to_string()));
+        }
+    }
+
+    // Workspaces and local packages are not installed
+    entries
+        .retain(|(_, version, _)| !version.is_empty() && !version.starts_with("0.0.0-use.local"));
+    for (specifiers, version, _) in &entries {
+        for (name, range) in specifiers {
+            lockfile.add(name, version, false);
+            lockfile.ranges.insert((name.clone(), range.clone()), version.clone());
+        }
+    }
+    for (specifiers, version, dependencies) in &entries {
+        let Some((name, _)) = specifiers.first() else {
+            continue;
+        };
+        for (dep, range) in dependencies {
+            if let Some(dep_version) = lockfile.ranges.get(&(dep.clone(), range.clone())).cloned() {
+                lockfile.depend((name, version), (dep, &dep_version));
+            }
+        }
+    }
+
+    lockfile
+}
+
+/// `@scope/name@npm:^1.0.0` into its name and range, without the protocol
+fn split_specifier(spec: &str) -> Option<(String, String)> {
+    let idx = spec.get(1..)?.find('@')? + 1;
+    let (name, range) = (&spec[..idx], &spec[idx + 1..]);
+    let range = range.strip_prefix("npm:").unwrap_or(range);
+    Some((name.to_string(), range.to_string()))
+}
+
+fn read_pnpm_lock(content: &str, member: &str) -> Result<Lockfile, String> {
+    let lock: serde_yaml::Value = serde_yaml::from_str(content).map_err(|e| e.to_string())?;
+    let mut lockfile = Lockfile::default();
+
+    let legacy = match &lock["lockfileVersion"] {
+        serde_yaml::Value::Number(n) => n.as_f64().is_some_and(|v| v < 6.0),
+        serde_yaml::Value::String(s) => s.parse::<f64>().is_ok_and(|v| v < 6.0),
+        _ => false,
+    };
+
+    // Workspaces (and every lockfile since v9) list the dependencies of each package
+    let importer = match lock.get("importers") {
+        Some(importers) => importers.get(member),
+        None => Some(&lock),
+    };
+    for section in ["dependencies", "devDependencies", "optionalDependencies"] {
+        let Some(deps) = importer.and_then(|i| i.get(section)).and_then(|deps| deps.as_mapping())
+        else {
+            continue;
+        };
+        for (name, entry) in deps {
+            let version = entry.as_str().or_else(|| entry.get("version")?.as_str());
+            if let (Some(name), Some(version)) = (name.as_str(), version) {
+                lockfile.installed.insert(name.to_string(), pnpm_version(version).to_string());
+            }
+        }
+    }
+
+    if let Some(packages) = lock.get("packages").and_then(|p| p.as_mapping()) {
+        for key in packages.keys().filter_map(|key| key.as_str()) {
+            if let Some((name, version)) = pnpm_package(key, legacy) {
+                lockfile.add(name, pnpm_version(version), false);
+            }
+        }
+    }
+
+    // Since v9 the dependencies of each package are listed apart, in snapshots
+    let snapshots = lock.get("snapshots").or_else(|| lock.get("packages"));
+    for (key, entry) in snapshots.and_then(|s| s.as_mapping()).into_iter().flatten() {
+        let Some((name, version)) = key.as_str().and_then(|key| pnpm_package(key, legacy)) else {
+            continue;
+        };
+        for section in ["dependencies", "optionalDependencies"] {
+            let Some(deps) = entry.get(section).and_then(|deps| deps.as_mapping()) else {
+                continue;
+            };
+            for (dep, dep_version) in deps {
+                let (Some(dep), Some(dep_version)) = (dep.as_str(), dep_version.as_str()) else {
+                    continue;
+                };
+                if dep_version.starts_with("link:") || dep_version.starts_with("file:") {
+                    continue;
+                }
+                // An alias points to another package: `string-width-cjs: string-width@4.2.3`
+                let (dep, dep_version) =
+                    pnpm_package(dep_version, legacy).unwrap_or((dep, dep_version));
+                lockfile.depend(
+                    (name, pnpm_version(version)),
+                    (dep, pnpm_version(dep_version)),
+                );
+            }
+        }
+    }
+
+    Ok(lockfile)
+}
+
+/// Name and version of a package: `/name/1.0.0` before v6, `/name@1.0.0` or `name@1.0.0` since
+fn pnpm_package(key: &str, legacy: bool) -> Option<(&str, &str)> {
+    let key = key.trim_start_matches('/');
+    if legacy {
+        return key.rsplit_once('/');
+    }
+    let key = key.split('(').next().unwrap_or(key);
+    key.get(1..)
+        .and_then(|k| k.rfind('@'))
+        .map(|idx| (&key[..idx + 1], &key[idx + 2..]))
+}
+
+/// Without the peer dependencies suffix: `18.2.0(react@18.2.0)` or `18.2.0_react@18.2.0`
+fn pnpm_version(version: &str) -> &str {
+    version.split(['(', '_']).next().unwrap_or(version)
+}
+
+/// bun.lock (text lockfile, since bun 1.2)
+fn read_bun_lock(content: &str, member: &str) -> Result<Lockfile, String> {
+    let lock: serde_json::Value =
+        serde_json::from_str(&strip_jsonc(content)).map_err(|e| e.to_string())?;
+    let mut lockfile = Lockfile::default();
+
+    let Some(packages) = lock["packages"].as_object() else {
+        return Ok(lockfile);
+    };
+    // "key": ["name@version", "registry", { dependencies }, "integrity"]
+    // Nested packages are keyed by their parent: "parent/name"
+    let installed = |key: &str| {
+        let (name, version) = packages.get(key)?[0].as_str().and_then(split_specifier)?;
+        Some((name, version)).filter(|(_, version)| !version.starts_with("workspace:"))
+    };
+    for (key, entry) in packages {
+        let Some((name, version)) = installed(key) else {
+            continue;
+        };
+        lockfile.add(&name, &version, false);
+
+        for section in ["dependencies", "optionalDependencies", "peerDependencies"] {
+            for dep in entry[2][section].as_object().into_iter().flat_map(|deps| deps.keys()) {
+                let found = locate_bun_package(packages, key, dep).and_then(installed);
+                if let Some((dep, dep_version)) = found {
+                    lockfile.depend((&name, &version), (&dep, &dep_version));
+                }
+            }
+        }
+    }
+
+    // The root package is keyed "", the packages of a workspace by their folder
+    let workspace = &lock["workspaces"][if member == "." { "" } else { member }];
+    // Its own copies are nested under its name
+    let base = workspace["name"].as_str().filter(|_| member != ".").unwrap_or("");
+    for section in [
+        "dependencies",
+        "devDependencies",
+        "optionalDependencies",
+        "peerDependencies",
+    ] {
+        for dep in workspace[section].as_object().into_iter().flat_map(|deps| deps.keys()) {
+            if let Some((_, version)) = locate_bun_package(packages, base, dep).and_then(installed)
+            {
+                lockfile.installed.insert(dep.to_string(), version);
+            }
+        }
+    }
+
+    Ok(lockfile)
+}
+
+/// Key of `name` as bun finds it from the package keyed `from`: nested under it, then under
+/// its parents up to the root.
+fn locate_bun_package<'a>(
+    packages: &'a serde_json::Map<String, serde_json::Value>,
+    from: &str,
+    name: &str,
+) -> Option<&'a str> {
+    let mut base = from;
+    loop {
+        let key = match base {
+            "" => name.to_string(),
+            base => format!("{}/{}", base, name),
+        };
+        if let Some((key, _)) = packages.get_key_value(&key) {
+            return Some(key);
+        }
+        if base.is_empty() {
+            return None;
+        }
+        // Without the last package of the key, its name can be scoped: `react/@scope/name`
+        let parent = base.rsplit_once('/').map_or("", |(parent, _)| parent);
+        let start = parent.rfind('/').map_or(0, |idx| idx + 1);
+        base = if parent[start..].starts_with('@') {
+            &parent[..start.saturating_sub(1)]
+        } else {
+            parent
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str, version: &str) -> Package {
+        (name.to_string(), version.to_string())
+    }
+
+    #[test]
+    fn test_read_package_lock() {
+        let v3 = r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "name": "app", "dependencies": { "express": "^4.17.1" } },
+    "node_modules/express": {
+      "version": "4.18.2",
+      "dependencies": { "debug": "2.6.9", "ms": "2.0.0", "@fake/api": "*" }
+    },
+    "node_modules/express/node_modules/debug": { "version": "2.6.9" },
+    "node_modules/ms": { "version": "2.0.0" },
+    "node_modules/@fake/api": { "resolved": "pkgs/api", "link": true },
+    "pkgs/api": { "version": "1.0.0", "dependencies": { "debug": "^4" } },
+    "pkgs/api/node_modules/debug": { "version": "4.3.4", "dependencies": { "ms": "^2" } }
+  }
+}"#;
+        let lockfile = read_package_lock(v3, ".").unwrap();
+        assert_eq!(lockfile.resolve("express", "^4.17.1").unwrap(), "4.18.2");
+        assert!(lockfile.resolve("debug", "*").is_none());
+        assert!(lockfile.resolve("@fake/api", "*").is_none());
+
+        // A member of the workspace, hoisted or not
+        let member = read_package_lock(v3, "pkgs/api").unwrap();
+        assert_eq!(member.resolve("debug", "^4").unwrap(), "4.3.4");
+        assert_eq!(member.resolve("express", "^4.17.1").unwrap(), "4.18.2");
+
+        assert_eq!(
+            lockfile.packages.into_keys().collect::<Vec<_>>(),
+            vec![
+                package("debug", "2.6.9"),
+                package("debug", "4.3.4"),
+                package("express", "4.18.2"),
+                package("ms", "2.0.0"),
+            ]
+        );
+        // Nested copies first, then the hoisted ones, packages of the workspace are left out
+        let lockfile = read_package_lock(v3, ".").unwrap();
+        assert_eq!(
+            lockfile.dependencies_of(vec![package("express", "4.18.2")]),
+            BTreeSet::from([
+                package("debug", "2.6.9"),
+                package("express", "4.18.2"),
+                package("ms", "2.0.0"),
+            ])
+        );
+        assert_eq!(
+            member.dependencies_of(vec![package("debug", "4.3.4")]),
+            BTreeSet::from([package("debug", "4.3.4"), package("ms", "2.0.0")])
+        );
+
+        let v1 = r#"{
+  "lockfileVersion": 1,
+  "dependencies": {
+    "express": {
+      "version": "4.17.1",
+      "requires": { "debug": "2.6.9", "ms": "^2.0.0" },
+      "dependencies": {
+        "debug": { "version": "2.6.9", "requires": { "ms": "2.0.0" } }
+      }
+    },
+    "ms": { "version": "2.1.3" }
+  }
+}"#;
+        let lockfile = read_package_lock(v1, ".").unwrap();
+        assert_eq!(lockfile.resolve("express", "^4.17.1").unwrap(), "4.17.1");
+        assert!(lockfile.resolve("debug", "*").is_none());
+        assert_eq!(lockfile.packages.len(), 3);
+        assert_eq!(
+            lockfile.dependencies_of(vec![package("express", "4.17.1")]),
+            BTreeSet::from([
+                package("debug", "2.6.9"),
+                package("express", "4.17.1"),
+                package("ms", "2.1.3"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_read_yarn_lock_classic() {
+        let content = r#"# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+"@babel/code-frame@^7.0.0", "@babel/code-frame@^7.10.4":
+  version "7.12.13"
+  resolved "https://registry.yarnpkg.com/@babel/code-frame/-/code-frame-7.12.13.tgz"
+  dependencies:
+    "@babel/highlight" "^7.10.4"
+    lodash "^4.17.21"
+
+"@babel/code-frame@^6.0.0":
+  version "6.26.0"
+
+lodash@^4.17.21:
+  version "4.17.21"
+"#;
+        let lockfile = read_yarn_lock(content);
+        assert_eq!(
+            lockfile.resolve("@babel/code-frame", "^7.10.4").unwrap(),
+            "7.12.13"
+        );
+        assert_eq!(
+            lockfile.resolve("@babel/code-frame", "^6.0.0").unwrap(),
+            "6.26.0"
+        );
+        // Only the ranges recorded are known, nothing is hoisted
+        assert_eq!(lockfile.resolve("lodash", "^4.17.21").unwrap(), "4.17.21");
+        assert!(lockfile.resolve("lodash", "4").is_none());
+        assert_eq!(lockfile.packages.len(), 3);
+        assert_eq!(
+            lockfile.dependencies_of(vec![package("@babel/code-frame", "7.12.13")]),
+            BTreeSet::from([
+                package("@babel/code-frame", "7.12.13"),
+                package("lodash", "4.17.21"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_read_yarn_lock_berry() {
+        let content = r#"# This file is generated by running "yarn install" inside your project.
+
+__metadata:
+  version: 6
+  cacheKey: 8
+
+"app@workspace:.":
+  version: 0.0.0-use.local
+  resolution: "app@workspace:."
+  dependencies:
+    react: ^18.2.0
+  languageName: unknown
+
+"loose-envify@npm:^1.1.0":
+  version: 1.4.0
+  resolution: "loose-envify@npm:1.4.0"
+
+"react@npm:^18.0.0, react@npm:^18.2.0":
+  version: 18.2.0
+  resolution: "react@npm:18.2.0"
+  dependencies:
+    loose-envify: ^1.1.0
+  checksum: 88e38092da8839b830cda6feef2e8505dec8ace60579e46aa5490fc3dc9bba0bd50336507dc166f43e3afc1c
+"#;
+        let lockfile = read_yarn_lock(content);
+        assert_eq!(lockfile.resolve("react", "^18.2.0").unwrap(), "18.2.0");
+        assert!(lockfile.resolve("app", "workspace:.").is_none());
+        assert_eq!(
+            lockfile.dependencies_of(vec![package("react", "18.2.0")]),
+            BTreeSet::from([package("loose-envify", "1.4.0"), package("react", "18.2.0")])
+        );
+    }
+
+    #[test]
+    fn test_read_pnpm_lock() {
+        let v9 = r#"lockfileVersion: '9.0'
+
+importers:
+  .:
+    dependencies:
+      react-dom:
+        specifier: ^18.2.0
+        version: 18.2.0(react@18.2.0)
+    devDependencies:
+      '@types/react':
+        specifier: ^18
+        version: 18.2.45
+  packages/ui:
+    dependencies:
+      react:
+        specifier: ^18
+        version: 18.2.0
+
+packages:
+  '@types/react@18.2.45':
+    resolution: {integrity: sha512-xxx}
+  react-dom@18.2.0:
+    resolution: {integrity: sha512-xxx}
+  react@18.2.0:
+    resolution: {integrity: sha512-xxx}
+
+snapshots:
+  '@types/react@18.2.45': {}
+  react-dom@18.2.0(react@18.2.0):
+    dependencies:
+      react: 18.2.0
+  react@18.2.0: {}
+"#;
+        let lockfile = read_pnpm_lock(v9, ".").unwrap();
+        assert_eq!(lockfile.resolve("react-dom", "^18.2.0").unwrap(), "18.2.0");
+        assert_eq!(lockfile.resolve("@types/react", "^18").unwrap(), "18.2.45");
+        assert!(lockfile.resolve("react", "^18").is_none());
+
+        let member = read_pnpm_lock(v9, "packages/ui").unwrap();
+        assert_eq!(member.resolve("react", "^18").unwrap(), "18.2.0");
+        assert!(member.resolve("react-dom", "^18.2.0").is_none());
+        assert!(read_pnpm_lock(v9, "packages/other").unwrap().resolve("react", "^18").is_none());
+        assert_eq!(
+            lockfile.dependencies_of(vec![package("react-dom", "18.2.0")]),
+            BTreeSet::from([package("react", "18.2.0"), package("react-dom", "18.2.0")])
+        );
+
+        assert_eq!(
+            lockfile.packages.into_keys().collect::<Vec<_>>(),
+            vec![
+                package("@types/react", "18.2.45"),
+                package("react", "18.2.0"),
+                package("react-dom", "18.2.0"),
+            ]
+        );
+
+        let v5 = r#"lockfileVersion: 5.4
+
+specifiers:
+  react-dom: ^18.2.0
+
+dependencies:
+  react-dom: 18.2.0_react@18.2.0
+
+packages:
+
+  /@babel/runtime/7.20.0:
+    resolution: {integrity: sha512-xxx}
+    dev: false
+
+  /react-dom/18.2.0_react@18.2.0:
+    resolution: {integrity: sha512-xxx}
+    dependencies:
+      '@babel/runtime': 7.20.0
+"#;
+        let lockfile = read_pnpm_lock(v5, ".").unwrap();
+        assert_eq!(lockfile.resolve("react-dom", "^18.2.0").unwrap(), "18.2.0");
+        assert_eq!(
+            lockfile.dependencies_of(vec![package("react-dom", "18.2.0")]),
+            BTreeSet::from([
+                package("@babel/runtime", "7.20.0"),
+                package("react-dom", "18.2.0")
+            ])
+        );
+        assert_eq!(
+            lockfile.packages.into_keys().collect::<Vec<_>>(),
+            vec![
+                package("@babel/runtime", "7.20.0"),
+                package("react-dom", "18.2.0")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_bun_lock() {
+        let content = r#"{
+  "lockfileVersion": 1,
+  "workspaces": {
+    "": {
+      "name": "app",
+      "dependencies": {
+        "@fake/api": "workspace:*",
+        "react": "^18.2.0",
+      },
+    },
+    "pkgs/api": {
+      "name": "@fake/api",
+      "dependencies": {
+        "react": "^17.0.0",
+      },
+    },
+  },
+  "packages": {
+    "@fake/api": ["@fake/api@workspace:pkgs/api"],
+    "@fake/api/react": ["react@17.0.2", "", {}, "sha512-xxx"],
+    "loose-envify": ["loose-envify@1.4.0", "", { "bin": { "loose-envify": "cli.js" } }, "sha512-xxx"],
+    "react": ["react@18.2.0", "", { "dependencies": { "loose-envify": "^1.1.0" } }, "sha512-xxx"],
+    "react/loose-envify": ["loose-envify@1.3.0", "", {}, "sha512-xxx"],
+  }
+}"#;
+        let lockfile = read_bun_lock(content, ".").unwrap();
+        assert_eq!(lockfile.resolve("react", "^18.2.0").unwrap(), "18.2.0");
+        // Not a dependency of the package, its version is not known
+        assert!(lockfile.resolve("loose-envify", "*").is_none());
+        assert!(lockfile.resolve("@fake/api", "workspace:*").is_none());
+        assert_eq!(lockfile.packages.len(), 4);
+        assert_eq!(
+            lockfile.dependencies_of(vec![package("react", "18.2.0")]),
+            BTreeSet::from([package("loose-envify", "1.3.0"), package("react", "18.2.0")])
+        );
+
+        let member = read_bun_lock(content, "pkgs/api").unwrap();
+        assert_eq!(member.resolve("react", "^17.0.0").unwrap(), "17.0.2");
+    }
+}
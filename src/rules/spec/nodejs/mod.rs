@@ -1,52 +1,57 @@
 pub mod component;
+pub mod lockfile;
+pub mod workspace;
 use component::detect_node_component;
 
 use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
 use crate::types::rule::{Rule, RuleDependency, RuleFiles};
 
-pub fn register_nodejs() -> Result<(), String> {
-    register(Rule {
-        tech: String::from("nodejs"),
-        name: String::from("NodeJS"),
-        r#type: String::from("language"),
-        files: Some(RuleFiles::FilesArray {
-            files: vec![String::from("package.json"), String::from(".nvmrc")],
-        }),
-        dependencies: Some(vec![
-            RuleDependency {
-                r#type: String::from("docker"),
-                name: Some(String::from("nodejs")),
-                example: Some(String::from("nodejs:0.0.0")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("docker"),
-                name: Some(String::from("node")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("docker"),
-                name: Some(String::from("circleci/node")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("docker"),
-                name: Some(String::from("cimg/node")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("docker"),
-                name: Some(String::from("bitnami/node")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("docker"),
-                name: Some(String::from("okteto/node")),
-                ..Default::default()
-            },
-        ]),
-        detect: Some(vec![detect_node_component]),
+pub fn register_nodejs(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("nodejs"),
+            name: String::from("NodeJS"),
+            r#type: String::from("language"),
+            files: Some(RuleFiles::FilesArray {
+                files: vec![String::from("package.json"), String::from(".nvmrc")],
+            }),
+            dependencies: Some(vec![
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("nodejs")),
+                    example: Some(String::from("nodejs:0.0.0")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("node")),
+                    example: Some(String::from("node:20-alpine")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("circleci/node")),
+                    example: Some(String::from("circleci/node:16")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("cimg/node")),
+                    example: Some(String::from("cimg/node:18.17")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("bitnami/node")),
+                    example: Some(String::from("bitnami/node:18")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("okteto/node")),
+                    example: Some(String::from("okteto/node:16")),
+                },
+            ]),
+            detect: Some(vec![detect_node_component]),
 
-        ..Default::default()
-    })
+            ..Default::default()
+        },
+    )
 }
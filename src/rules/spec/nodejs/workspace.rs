@@ -0,0 +1,380 @@
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::provider::base::{
+    matches_glob, normalize_path, BaseProvider, FileType, IGNORED_DIVE_PATHS,
+};
+
+/// Packages of a monorepo, declared in package.json or by the tool managing it.
+#[derive(Debug, Default)]
+pub struct Workspace {
+    /// Folder of the monorepo root
+    pub root: PathBuf,
+    /// Files declaring the monorepo, e.g: `pnpm-workspace.yaml`
+    pub files: Vec<String>,
+    /// Folder of each member package, by name
+    pub packages: BTreeMap<String, PathBuf>,
+}
+
+thread_local! {
+    /// Workspace read in each folder, only kept while a `WorkspaceCache` is alive
+    static CACHE: RefCell<Option<HashMap<PathBuf, Option<Rc<Workspace>>>>> =
+        const { RefCell::new(None) };
+}
+
+/// Keeps the workspaces read until dropped, so each root is only resolved once.
+/// The provider must stay the same meanwhile, e.g: for the duration of an analysis.
+pub struct WorkspaceCache(());
+
+impl WorkspaceCache {
+    pub fn enable() -> Self {
+        CACHE.with(|cache| *cache.borrow_mut() = Some(HashMap::new()));
+        WorkspaceCache(())
+    }
+}
+
+impl Drop for WorkspaceCache {
+    fn drop(&mut self) {
+        CACHE.with(|cache| *cache.borrow_mut() = None);
+    }
+}
+
+/// Workspace a package.json belongs to, as its root or as one of its members.
+pub fn find_workspace(manifest: &str, provider: &dyn BaseProvider) -> Option<Rc<Workspace>> {
+    let folder = normalize_path(Path::new(manifest).parent()?);
+    let base = provider.base_path();
+
+    for dir in folder.ancestors() {
+        if !dir.starts_with(&base) {
+            break;
+        }
+
+        let Some(workspace) = cached_workspace(dir, provider) else {
+            continue;
+        };
+        // A package can live in a monorepo without being part of it, e.g: examples
+        if dir == folder || workspace.packages.values().any(|path| *path == folder) {
+            return Some(workspace);
+        }
+    }
+
+    None
+}
+
+fn cached_workspace(dir: &Path, provider: &dyn BaseProvider) -> Option<Rc<Workspace>> {
+    let cached = CACHE.with(|cache| cache.borrow().as_ref().map(|c| c.get(dir).cloned()));
+    if let Some(Some(workspace)) = cached {
+        return workspace;
+    }
+
+    let workspace = read_workspace(dir, provider).map(Rc::new);
+    // Only stored if the cache is enabled
+    if cached.is_some() {
+        CACHE.with(|cache| {
+            if let Some(cache) = cache.borrow_mut().as_mut() {
+                cache.insert(dir.to_path_buf(), workspace.clone());
+            }
+        });
+    }
+    workspace
+}
+
+fn read_workspace(dir: &Path, provider: &dyn BaseProvider) -> Option<Workspace> {
+    let mut globs: Vec<String> = Vec::new();
+    let mut files: Vec<String> = Vec::new();
+
+    // npm, yarn and bun: `["pkgs/*"]`, or `{ "packages": ["pkgs/*"] }` for yarn classic
+    let fp = dir.join("package.json").to_string_lossy().to_string();
+    if let Some(package_json) = open_json(&fp, provider) {
+        let workspaces = &package_json["workspaces"];
+        let declared = strings(workspaces.get("packages").unwrap_or(workspaces));
+        if !declared.is_empty() {
+            globs.extend(declared);
+            files.push(fp);
+        }
+    }
+
+    let fp = dir.join("pnpm-workspace.yaml").to_string_lossy().to_string();
+    if let Some(content) = provider.open(&fp) {
+        match serde_yaml::from_str::<Value>(&content) {
+            Ok(pnpm) => {
+                globs.extend(strings(&pnpm["packages"]));
+                files.push(fp);
+            }
+            Err(e) => log::warn!("Failed to parse pnpm-workspace.yaml: {} - {}", fp, e),
+        }
+    }
+
+    let fp = dir.join("lerna.json").to_string_lossy().to_string();
+    if let Some(lerna) = open_json(&fp, provider) {
+        let declared = strings(&lerna["packages"]);
+        if declared.is_empty() {
+            globs.push("packages/*".to_string());
+        }
+        globs.extend(declared);
+        files.push(fp);
+    }
+
+    // Nx before v15 did not rely on the package manager to find the projects
+    let fp = dir.join("nx.json").to_string_lossy().to_string();
+    if let Some(nx) = open_json(&fp, provider) {
+        for layout in ["appsDir", "libsDir"] {
+            if let Some(folder) = nx["workspaceLayout"][layout].as_str() {
+                globs.push(format!("{}/*", folder));
+            }
+        }
+        files.push(fp);
+    }
+
+    // Turborepo only runs the tasks, packages come from the package manager
+    let fp = dir.join("turbo.json").to_string_lossy().to_string();
+    if provider.open(&fp).is_some() {
+        files.push(fp);
+    }
+
+    if files.is_empty() {
+        return None;
+    }
+
+    globs.sort();
+    globs.dedup();
+    Some(Workspace {
+        root: dir.to_path_buf(),
+        files,
+        packages: expand_globs(dir, &globs, provider),
+    })
+}
+
+fn open_json(fp: &str, provider: &dyn BaseProvider) -> Option<Value> {
+    let content = provider.open(fp)?;
+    match serde_json::from_str(&content) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            log::warn!("Failed to parse {}: {}", fp, e);
+            None
+        }
+    }
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|list| list.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+/// Name and folder of the packages matched by the globs, `!` excludes folders
+fn expand_globs(
+    root: &Path,
+    globs: &[String],
+    provider: &dyn BaseProvider,
+) -> BTreeMap<String, PathBuf> {
+    let (excludes, includes): (Vec<&String>, Vec<&String>) =
+        globs.iter().partition(|glob| glob.starts_with('!'));
+
+    let mut packages = BTreeMap::new();
+    for glob in includes {
+        let mut dirs = vec![root.to_path_buf()];
+        for segment in glob.split('/').filter(|s| !s.is_empty() && *s != ".") {
+            dirs = dirs
+                .iter()
+                .flat_map(|dir| match segment {
+                    "**" => descendants(dir, provider),
+                    _ => sub_dirs(dir, provider)
+                        .into_iter()
+                        .filter(|sub| {
+                            sub.file_name()
+                                .is_some_and(|name| matches_glob(segment, Path::new(name)))
+                        })
+                        .collect(),
+                })
+                .collect();
+        }
+
+        for dir in dirs {
+            let relative = dir.strip_prefix(root).unwrap_or(&dir);
+            if excludes.iter().any(|glob| matches_glob(&glob[1..], relative)) {
+                continue;
+            }
+
+            let fp = dir.join("package.json").to_string_lossy().to_string();
+            let Some(package_json) = open_json(&fp, provider) else {
+                continue;
+            };
+            if let Some(name) = package_json["name"].as_str() {
+                packages.entry(name.to_string()).or_insert(dir);
+            }
+        }
+    }
+
+    packages
+}
+
+fn sub_dirs(dir: &Path, provider: &dyn BaseProvider) -> Vec<PathBuf> {
+    // Folders are listed with a trailing slash
+    let key = format!("{}/", dir.to_string_lossy().trim_end_matches('/'));
+    provider
+        .list_dir(&key)
+        .into_iter()
+        .filter(|f| f.file_type == FileType::Dir && !IGNORED_DIVE_PATHS.contains(&f.name.as_str()))
+        .map(|f| normalize_path(Path::new(&f.fp)))
+        .collect()
+}
+
+/// The folder and every folder below it, for `**`
+fn descendants(dir: &Path, provider: &dyn BaseProvider) -> Vec<PathBuf> {
+    let mut result = vec![dir.to_path_buf()];
+    for sub in sub_dirs(dir, provider) {
+        result.extend(descendants(&sub, provider));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{base::ProviderFile, fake::FakeProvider};
+    use std::cell::Cell;
+
+    fn monorepo(root_files: Vec<(&str, &str)>) -> FakeProvider {
+        let mut files: HashMap<String, String> = HashMap::from_iter([
+            (
+                "/apps/web/package.json".to_string(),
+                r#"{ "name": "web" }"#.to_string(),
+            ),
+            (
+                "/packages/ui/package.json".to_string(),
+                r#"{ "name": "@acme/ui" }"#.to_string(),
+            ),
+            (
+                "/packages/legacy/package.json".to_string(),
+                r#"{ "name": "legacy" }"#.to_string(),
+            ),
+        ]);
+        for (name, content) in root_files {
+            files.insert(format!("/{}", name), content.to_string());
+        }
+
+        FakeProvider::new(
+            HashMap::from_iter([
+                (
+                    "/".to_string(),
+                    vec!["apps/".to_string(), "packages/".to_string()],
+                ),
+                ("/apps/".to_string(), vec!["web/".to_string()]),
+                (
+                    "/packages/".to_string(),
+                    vec![
+                        "legacy/".to_string(),
+                        "node_modules/".to_string(),
+                        "ui/".to_string(),
+                    ],
+                ),
+            ]),
+            files,
+        )
+    }
+
+    fn names(workspace: &Workspace) -> Vec<&str> {
+        workspace.packages.keys().map(|name| name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_find_workspace_package_json() {
+        let provider = monorepo(vec![(
+            "package.json",
+            r#"{ "name": "root", "workspaces": { "packages": ["apps/*", "packages/ui"] } }"#,
+        )]);
+
+        let workspace = find_workspace("/apps/web/package.json", &provider).unwrap();
+        assert_eq!(workspace.root, PathBuf::from("/"));
+        assert_eq!(workspace.files, vec!["/package.json"]);
+        assert_eq!(names(&workspace), vec!["@acme/ui", "web"]);
+        assert_eq!(workspace.packages["web"], PathBuf::from("/apps/web"));
+
+        assert!(find_workspace("/package.json", &provider).is_some());
+        // Not a member
+        assert!(find_workspace("/packages/legacy/package.json", &provider).is_none());
+    }
+
+    #[test]
+    fn test_find_workspace_pnpm() {
+        let provider = monorepo(vec![
+            (
+                "pnpm-workspace.yaml",
+                "packages:\n  - 'apps/**'\n  - 'packages/*'\n  - '!packages/legacy'\n",
+            ),
+            ("turbo.json", "{}"),
+        ]);
+
+        let workspace = find_workspace("/packages/ui/package.json", &provider).unwrap();
+        assert_eq!(workspace.files, vec!["/pnpm-workspace.yaml", "/turbo.json"]);
+        assert_eq!(names(&workspace), vec!["@acme/ui", "web"]);
+    }
+
+    #[test]
+    fn test_find_workspace_lerna_nx() {
+        let provider = monorepo(vec![("lerna.json", r#"{ "version": "1.0.0" }"#)]);
+        let workspace = find_workspace("/package.json", &provider).unwrap();
+        assert_eq!(names(&workspace), vec!["@acme/ui", "legacy"]);
+
+        let provider = monorepo(vec![(
+            "nx.json",
+            r#"{ "workspaceLayout": { "appsDir": "apps", "libsDir": "packages" } }"#,
+        )]);
+        let workspace = find_workspace("/package.json", &provider).unwrap();
+        assert_eq!(names(&workspace), vec!["@acme/ui", "legacy", "web"]);
+    }
+
+    #[derive(Debug)]
+    struct CountingProvider {
+        inner: FakeProvider,
+        listed: Cell<usize>,
+    }
+
+    impl BaseProvider for CountingProvider {
+        fn list_dir(&self, path: &str) -> Vec<ProviderFile> {
+            self.listed.set(self.listed.get() + 1);
+            self.inner.list_dir(path)
+        }
+        fn base_path(&self) -> String {
+            self.inner.base_path()
+        }
+        fn open(&self, path: &str) -> Option<String> {
+            self.inner.open(path)
+        }
+    }
+
+    #[test]
+    fn test_find_workspace_cached() {
+        let provider = CountingProvider {
+            inner: monorepo(vec![(
+                "package.json",
+                r#"{ "workspaces": ["packages/*", "apps/*", "packages/*"] }"#,
+            )]),
+            listed: Cell::new(0),
+        };
+
+        // Duplicated globs are only expanded once
+        find_workspace("/package.json", &provider).unwrap();
+        assert_eq!(provider.listed.get(), 4);
+
+        // Without a cache, the root is read again for every package
+        find_workspace("/package.json", &provider).unwrap();
+        assert_eq!(provider.listed.get(), 8);
+
+        {
+            let _cache = WorkspaceCache::enable();
+            let web = find_workspace("/apps/web/package.json", &provider).unwrap();
+            let ui = find_workspace("/packages/ui/package.json", &provider).unwrap();
+            assert!(Rc::ptr_eq(&web, &ui));
+            assert_eq!(provider.listed.get(), 12);
+        }
+
+        find_workspace("/package.json", &provider).unwrap();
+        assert_eq!(provider.listed.get(), 16);
+    }
+}
@@ -0,0 +1,254 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    payload::payload::{dependency, Payload},
+    provider::base::{folder_name, BaseProvider, ProviderFile},
+    rules::{match_dependencies::match_dependencies, registry::RuleRegistry},
+};
+
+const FILES: [&str; 1] = ["composer.json"];
+const LOCKFILE: &str = "composer.lock";
+
+/// Packages provided by the platform rather than installed by composer.
+const PLATFORM_PACKAGES: [&str; 8] = [
+    "php",
+    "php-64bit",
+    "php-ipv6",
+    "php-zts",
+    "php-debug",
+    "hhvm",
+    "composer-plugin-api",
+    "composer-runtime-api",
+];
+
+#[derive(Debug, Deserialize)]
+struct ComposerJson {
+    name: Option<String>,
+    require: Option<BTreeMap<String, String>>,
+    #[serde(rename = "require-dev")]
+    require_dev: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ComposerLock {
+    #[serde(default)]
+    packages: Vec<LockPackage>,
+    #[serde(default, rename = "packages-dev")]
+    packages_dev: Vec<LockPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LockPackage {
+    name: String,
+    version: String,
+}
+
+pub fn detect_php_component(
+    files: &Vec<ProviderFile>,
+    provider: &dyn BaseProvider,
+    registry: &RuleRegistry,
+) -> Result<Payload, bool> {
+    for file in files {
+        if !FILES.contains(&file.name.as_str()) {
+            continue;
+        }
+
+        let content = match provider.open(&file.fp) {
+            Some(content) => content,
+            None => continue,
+        };
+
+        let composer_json: ComposerJson = match serde_json::from_str(&content) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                log::warn!("Failed to parse composer.json: {} - {}", file.fp, e);
+                continue;
+            }
+        };
+
+        // Applications usually don't have a name, only published packages do
+        let name = composer_json.name.unwrap_or_else(|| folder_name(&file.fp, provider));
+
+        let mut pl = Payload::new(&name, &file.fp);
+
+        let locked = match files.iter().find(|f| f.name == LOCKFILE) {
+            Some(lockfile) => {
+                pl.path.insert(lockfile.fp.clone());
+                read_lockfile(lockfile, provider)
+            }
+            None => HashMap::new(),
+        };
+
+        let mut packages: Vec<String> = Vec::new();
+        let mut deps_flatten: Vec<Vec<String>> = Vec::new();
+        let mut platform: Vec<Vec<String>> = Vec::new();
+        let requires = composer_json
+            .require
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, constraint)| (name, constraint, ""))
+            .chain(
+                composer_json
+                    .require_dev
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(name, constraint)| (name, constraint, "dev")),
+            );
+        for (name, constraint, scope) in requires {
+            // Runtime constraints, e.g: php >=8.0 or ext-gd
+            if is_platform_package(&name) {
+                platform.push(dependency("php", &name, "", &constraint, scope));
+                continue;
+            }
+            if packages.contains(&name) {
+                continue;
+            }
+
+            let resolved = locked.get(&name).cloned().unwrap_or_default();
+            deps_flatten.push(dependency("php", &name, &resolved, &constraint, scope));
+            packages.push(name);
+        }
+        deps_flatten.extend(platform);
+
+        let techs = match_dependencies(&packages, "php", registry);
+
+        pl.add_techs(&techs, registry);
+        pl.dependencies = deps_flatten;
+
+        return Ok(pl);
+    }
+
+    Err(false)
+}
+
+fn is_platform_package(name: &str) -> bool {
+    PLATFORM_PACKAGES.contains(&name) || name.starts_with("ext-") || name.starts_with("lib-")
+}
+
+/// Exact versions installed, by package name.
+fn read_lockfile(file: &ProviderFile, provider: &dyn BaseProvider) -> HashMap<String, String> {
+    let Some(content) = provider.open(&file.fp) else {
+        return HashMap::new();
+    };
+
+    match serde_json::from_str::<ComposerLock>(&content) {
+        Ok(lock) => lock
+            .packages
+            .into_iter()
+            .chain(lock.packages_dev)
+            .map(|pkg| (pkg.name, pkg.version))
+            .collect(),
+        Err(e) => {
+            log::warn!("Failed to parse composer.lock: {} - {}", file.fp, e);
+            HashMap::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{fake::FakeProvider, fs::FsProvider};
+    use crate::types::rule::{Rule, RuleDependency};
+
+    fn dep(name: &str, resolved: &str, declared: &str, scope: &str) -> Vec<String> {
+        dependency("php", name, resolved, declared, scope)
+    }
+
+    #[test]
+    fn test_detect_php_component() {
+        let composer_json = r#"{
+    "require": {
+        "php": "^8.1",
+        "ext-pdo_pgsql": "*",
+        "laravel/framework": "^10.0",
+        "zumba/amplitude-php": "^1.0"
+    },
+    "require-dev": {
+        "phpunit/phpunit": "^10.1"
+    }
+}"#;
+        let composer_lock = r#"{
+    "packages": [{ "name": "laravel/framework", "version": "v10.13.0" }],
+    "packages-dev": [{ "name": "phpunit/phpunit", "version": "10.1.3" }]
+}"#;
+
+        let provider = FakeProvider::new(
+            HashMap::from_iter([(
+                "/app".to_string(),
+                vec!["composer.json".to_string(), "composer.lock".to_string()],
+            )]),
+            HashMap::from_iter([
+                ("/app/composer.json".to_string(), composer_json.to_string()),
+                ("/app/composer.lock".to_string(), composer_lock.to_string()),
+            ]),
+        );
+
+        let registry = RuleRegistry::from_rules(vec![Rule {
+            tech: "php".to_string(),
+            name: "PHP".to_string(),
+            r#type: "language".to_string(),
+            dependencies: Some(vec![RuleDependency {
+                r#type: "php".to_string(),
+                name: Some("php".to_string()),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }])
+        .unwrap();
+
+        let result =
+            detect_php_component(&provider.list_dir("/app"), &provider, &registry).unwrap();
+
+        assert_eq!(result.name, "app");
+        assert_eq!(result.path.len(), 2);
+        assert_eq!(
+            result.dependencies,
+            vec![
+                dep("laravel/framework", "v10.13.0", "^10.0", ""),
+                dep("zumba/amplitude-php", "", "^1.0", ""),
+                dep("phpunit/phpunit", "10.1.3", "^10.1", "dev"),
+                dep("ext-pdo_pgsql", "", "*", ""),
+                dep("php", "", "^8.1", ""),
+            ]
+        );
+        // Platform packages are never matched against rules
+        assert!(result.techs.is_empty());
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let result =
+            detect_php_component(&provider.list_dir("/app"), &provider, &registry).unwrap();
+        assert!(result.techs.contains("amplitude"));
+    }
+
+    #[test]
+    fn test_detect_php_fixture() {
+        let provider = FsProvider::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/__fixtures__/php"
+        ))
+        .unwrap();
+        let registry = RuleRegistry::with_defaults().unwrap();
+
+        let files = provider.list_dir(&provider.base_path());
+        let result = detect_php_component(&files, &provider, &registry).unwrap();
+
+        assert_eq!(result.name, "symfony/translation");
+        assert_eq!(result.dependencies.len(), 36);
+        assert!(result.dependencies.contains(&dep("laravel/framework", "", "^9.0", "dev")));
+        assert!(result.dependencies.contains(&dep("php", "", ">=8.0", "dev")));
+        assert!(result.dependencies.contains(&dep("ext-gd", "", "*", "dev")));
+    }
+
+    #[test]
+    fn test_detect_php_invalid() {
+        let provider = FakeProvider::new(
+            HashMap::from_iter([("/".to_string(), vec!["composer.json".to_string()])]),
+            HashMap::from_iter([("/composer.json".to_string(), "{".to_string())]),
+        );
+
+        let res = detect_php_component(&provider.list_dir("/"), &provider, &RuleRegistry::new());
+        assert!(res.is_err());
+    }
+}
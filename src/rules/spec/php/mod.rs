@@ -0,0 +1,39 @@
+pub mod component;
+use component::detect_php_component;
+
+use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
+use crate::types::rule::{Rule, RuleDependency, RuleFiles};
+
+pub fn register_php(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("php"),
+            name: String::from("PHP"),
+            r#type: String::from("language"),
+            files: Some(RuleFiles::FilesArray {
+                files: vec![String::from("composer.json"), String::from("composer.lock")],
+            }),
+            dependencies: Some(vec![
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("php")),
+                    example: Some(String::from("php:8.2-fpm")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("circleci/php")),
+                    example: Some(String::from("circleci/php:8.0")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("cimg/php")),
+                    example: Some(String::from("cimg/php:8.2")),
+                },
+            ]),
+            detect: Some(vec![detect_php_component]),
+            ..Default::default()
+        },
+    )
+}
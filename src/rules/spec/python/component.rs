@@ -0,0 +1,809 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use crate::{
+    payload::payload::{dependency, Payload},
+    provider::base::{folder_name, normalize_path, BaseProvider, ProviderFile},
+    rules::{match_dependencies::match_dependencies, registry::RuleRegistry},
+};
+
+const PYPROJECT: &str = "pyproject.toml";
+const SETUP_CFG: &str = "setup.cfg";
+const PIPFILE: &str = "Pipfile";
+/// Only used to know which version is installed, they never declare a project on their own.
+const LOCKFILES: [&str; 3] = ["poetry.lock", "pdm.lock", "Pipfile.lock"];
+
+#[derive(Debug, Deserialize)]
+struct PyProject {
+    project: Option<Project>,
+    tool: Option<Tool>,
+    #[serde(rename = "dependency-groups")]
+    dependency_groups: Option<HashMap<String, Vec<toml::Value>>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Project {
+    name: Option<String>,
+    #[serde(rename = "requires-python")]
+    requires_python: Option<String>,
+    dependencies: Option<Vec<String>>,
+    #[serde(rename = "optional-dependencies")]
+    optional_dependencies: Option<HashMap<String, Vec<String>>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tool {
+    poetry: Option<Poetry>,
+    pdm: Option<Pdm>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Poetry {
+    name: Option<String>,
+    dependencies: Option<HashMap<String, TomlDependency>>,
+    #[serde(rename = "dev-dependencies")]
+    dev_dependencies: Option<HashMap<String, TomlDependency>>,
+    group: Option<HashMap<String, PoetryGroup>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PoetryGroup {
+    dependencies: Option<HashMap<String, TomlDependency>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pdm {
+    #[serde(rename = "dev-dependencies")]
+    dev_dependencies: Option<HashMap<String, Vec<String>>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pipfile {
+    packages: Option<HashMap<String, TomlDependency>>,
+    #[serde(rename = "dev-packages")]
+    dev_packages: Option<HashMap<String, TomlDependency>>,
+    requires: Option<PipfileRequires>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PipfileRequires {
+    python_version: Option<String>,
+    python_full_version: Option<String>,
+}
+
+/// Dependency as written in Poetry or Pipfile sections.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum TomlDependency {
+    Simple(String),
+    Detailed {
+        version: Option<String>,
+        path: Option<String>,
+        git: Option<String>,
+        branch: Option<String>,
+        rev: Option<String>,
+        tag: Option<String>,
+        #[serde(rename = "ref")]
+        git_ref: Option<String>,
+    },
+    Multiple(Vec<TomlDependency>),
+}
+
+#[derive(Debug, Deserialize)]
+struct TomlLock {
+    #[serde(default)]
+    package: Vec<LockPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LockPackage {
+    name: String,
+    version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PipfileLock {
+    #[serde(default)]
+    default: HashMap<String, PipfileLockPackage>,
+    #[serde(default)]
+    develop: HashMap<String, PipfileLockPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PipfileLockPackage {
+    version: Option<String>,
+}
+
+/// Everything declared by the manifests of a single folder.
+#[derive(Debug, Default)]
+struct PythonProject {
+    name: Option<String>,
+    python: Option<String>,
+    /// Declared version and scope, by normalized name
+    dependencies: BTreeMap<String, (String, &'static str)>,
+}
+
+pub fn detect_python_component(
+    files: &Vec<ProviderFile>,
+    provider: &dyn BaseProvider,
+    registry: &RuleRegistry,
+) -> Result<Payload, bool> {
+    let mut project = PythonProject::default();
+    let mut manifests: Vec<&ProviderFile> = Vec::new();
+
+    // Most precise manifests first, the first one declaring a dependency wins
+    if let Some(file) = files.iter().find(|f| f.name == PYPROJECT) {
+        if let Some(content) = provider.open(&file.fp) {
+            match toml::from_str::<PyProject>(&content) {
+                Ok(pyproject) => {
+                    project.read_pyproject(pyproject);
+                    manifests.push(file);
+                }
+                Err(e) => log::warn!("Failed to parse pyproject.toml: {} - {}", file.fp, e),
+            }
+        }
+    }
+
+    if let Some(file) = files.iter().find(|f| f.name == SETUP_CFG) {
+        if let Some(content) = provider.open(&file.fp) {
+            project.read_setup_cfg(&content);
+            manifests.push(file);
+        }
+    }
+
+    if let Some(file) = files.iter().find(|f| f.name == PIPFILE) {
+        if let Some(content) = provider.open(&file.fp) {
+            match toml::from_str::<Pipfile>(&content) {
+                Ok(pipfile) => {
+                    project.read_pipfile(pipfile);
+                    manifests.push(file);
+                }
+                Err(e) => log::warn!("Failed to parse Pipfile: {} - {}", file.fp, e),
+            }
+        }
+    }
+
+    let mut visited = HashSet::new();
+    for file in files {
+        if !is_requirements(&file.name) {
+            continue;
+        }
+        if project.read_requirements(provider, &file.fp, &mut visited) {
+            manifests.push(file);
+        }
+    }
+
+    let Some(first) = manifests.first() else {
+        return Err(false);
+    };
+
+    let locked = read_lockfiles(files, provider);
+
+    let name = project.name.clone().unwrap_or_else(|| folder_name(&first.fp, provider));
+
+    let mut pl = Payload::new(&name, &first.fp);
+    for file in &manifests[1..] {
+        pl.path.insert(file.fp.clone());
+    }
+
+    let techs = match_dependencies(
+        &project.dependencies.keys().cloned().collect::<Vec<_>>(),
+        "python",
+        registry,
+    );
+
+    let deps_flatten: Vec<Vec<String>> = project
+        .dependencies
+        .into_iter()
+        .map(|(name, (declared, scope))| {
+            let resolved = if declared.starts_with("path:") {
+                declared.clone()
+            } else if declared.starts_with("git:") || declared.contains("://") {
+                String::new()
+            } else {
+                locked.get(&name).cloned().unwrap_or_default()
+            };
+            dependency("python", &name, &resolved, &declared, scope)
+        })
+        .collect();
+
+    if let Some(python) = project.python {
+        pl.reason.insert(format!("python: {}", python));
+    }
+
+    pl.add_techs(&techs, registry);
+    pl.dependencies = deps_flatten;
+
+    Ok(pl)
+}
+
+impl PythonProject {
+    fn add(&mut self, name: &str, version: String, scope: &'static str) {
+        self.dependencies.entry(normalize_name(name)).or_insert((version, scope));
+    }
+
+    fn add_requirement(&mut self, requirement: &str, scope: &'static str) {
+        match parse_requirement(requirement) {
+            Some((name, version)) => self.add(&name, version, scope),
+            None => log::debug!("Ignored python requirement: {}", requirement),
+        }
+    }
+
+    fn add_toml_dependencies(
+        &mut self,
+        deps: Option<HashMap<String, TomlDependency>>,
+        scope: &'static str,
+    ) {
+        for (name, dep) in deps.unwrap_or_default() {
+            // Poetry declares the interpreter alongside the packages
+            if name == "python" {
+                self.python.get_or_insert(dep.version());
+                continue;
+            }
+            self.add(&name, dep.version(), scope);
+        }
+    }
+
+    fn read_pyproject(&mut self, pyproject: PyProject) {
+        if let Some(project) = pyproject.project {
+            self.name = self.name.take().or(project.name);
+            if let Some(python) = project.requires_python {
+                self.python.get_or_insert(python);
+            }
+            for requirement in project.dependencies.unwrap_or_default() {
+                self.add_requirement(&requirement, "");
+            }
+            for requirements in project.optional_dependencies.unwrap_or_default().into_values() {
+                for requirement in requirements {
+                    self.add_requirement(&requirement, "optional");
+                }
+            }
+        }
+
+        if let Some(tool) = pyproject.tool {
+            if let Some(poetry) = tool.poetry {
+                self.name = self.name.take().or(poetry.name);
+                self.add_toml_dependencies(poetry.dependencies, "");
+                self.add_toml_dependencies(poetry.dev_dependencies, "dev");
+                // Only the implicit main group is installed with the package
+                for (name, group) in poetry.group.unwrap_or_default() {
+                    let scope = if name == "main" { "" } else { "dev" };
+                    self.add_toml_dependencies(group.dependencies, scope);
+                }
+            }
+            if let Some(pdm) = tool.pdm {
+                for requirements in pdm.dev_dependencies.unwrap_or_default().into_values() {
+                    for requirement in requirements {
+                        self.add_requirement(&requirement, "dev");
+                    }
+                }
+            }
+        }
+
+        // PEP 735, entries can also be tables including another group
+        for requirements in pyproject.dependency_groups.unwrap_or_default().into_values() {
+            for requirement in requirements {
+                if let Some(requirement) = requirement.as_str() {
+                    self.add_requirement(requirement, "dev");
+                }
+            }
+        }
+    }
+
+    fn read_setup_cfg(&mut self, content: &str) {
+        let sections = parse_ini(content);
+
+        if let Some(name) = sections.get("metadata").and_then(|s| s.get("name")) {
+            self.name.get_or_insert(name.trim().to_string());
+        }
+
+        if let Some(options) = sections.get("options") {
+            if let Some(python) = options.get("python_requires") {
+                self.python.get_or_insert(python.trim().to_string());
+            }
+            if let Some(requirements) = options.get("install_requires") {
+                requirements.lines().for_each(|r| self.add_requirement_line(r, ""));
+            }
+        }
+
+        if let Some(extras) = sections.get("options.extras_require") {
+            for requirements in extras.values() {
+                requirements.lines().for_each(|r| self.add_requirement_line(r, "optional"));
+            }
+        }
+    }
+
+    fn add_requirement_line(&mut self, line: &str, scope: &'static str) {
+        let line = line.trim();
+        if !line.is_empty() {
+            self.add_requirement(line, scope);
+        }
+    }
+
+    fn read_pipfile(&mut self, pipfile: Pipfile) {
+        if let Some(requires) = pipfile.requires {
+            if let Some(python) = requires.python_full_version.or(requires.python_version) {
+                self.python.get_or_insert(python);
+            }
+        }
+        self.add_toml_dependencies(pipfile.packages, "");
+        self.add_toml_dependencies(pipfile.dev_packages, "dev");
+    }
+
+    /// Read a requirements file and the ones it includes with `-r`.
+    /// Returns false if the file could not be read.
+    fn read_requirements(
+        &mut self,
+        provider: &dyn BaseProvider,
+        fp: &str,
+        visited: &mut HashSet<String>,
+    ) -> bool {
+        if !visited.insert(fp.to_string()) {
+            return true;
+        }
+
+        let Some(content) = provider.open(fp) else {
+            log::warn!("Failed to open python requirements: {}", fp);
+            return false;
+        };
+
+        let content = content.replace("\\\r\n", "").replace("\\\n", "");
+        for line in content.lines() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(include) = requirements_include(line) {
+                let parent = Path::new(fp).parent().unwrap_or(Path::new(""));
+                let include_fp = normalize_path(&parent.join(include));
+                self.read_requirements(provider, &include_fp.to_string_lossy(), visited);
+                continue;
+            }
+
+            // Other options (-c, -e, --index-url, ...) don't declare a dependency
+            if line.starts_with('-') {
+                continue;
+            }
+
+            self.add_requirement(line, "");
+        }
+
+        true
+    }
+}
+
+impl TomlDependency {
+    fn version(&self) -> String {
+        match self {
+            TomlDependency::Simple(version) => clean_version(version),
+            TomlDependency::Detailed {
+                version,
+                path,
+                git,
+                branch,
+                rev,
+                tag,
+                git_ref,
+            } => {
+                if let Some(path) = path {
+                    format!("path:{}", path)
+                } else if let Some(git) = git {
+                    let suffix = branch
+                        .as_ref()
+                        .or(rev.as_ref())
+                        .or(tag.as_ref())
+                        .or(git_ref.as_ref())
+                        .map(|s| s.as_str())
+                        .unwrap_or("latest");
+                    format!("git:{}#{}", git, suffix)
+                } else {
+                    version.as_deref().map(clean_version).unwrap_or_else(|| "latest".to_string())
+                }
+            }
+            // Different constraints per platform or python version, the first one is enough
+            TomlDependency::Multiple(list) => {
+                list.first().map(|d| d.version()).unwrap_or_else(|| "latest".to_string())
+            }
+        }
+    }
+}
+
+/// Exact versions found in the lockfiles of the folder, by normalized name.
+fn read_lockfiles(files: &[ProviderFile], provider: &dyn BaseProvider) -> HashMap<String, String> {
+    let mut locked = HashMap::new();
+
+    for file in files.iter().filter(|f| LOCKFILES.contains(&f.name.as_str())) {
+        let Some(content) = provider.open(&file.fp) else {
+            continue;
+        };
+
+        let packages: Result<Vec<(String, String)>, String> = if file.name == "Pipfile.lock" {
+            serde_json::from_str::<PipfileLock>(&content)
+                .map(|lock| {
+                    lock.default
+                        .into_iter()
+                        .chain(lock.develop)
+                        .filter_map(|(name, pkg)| Some((name, clean_version(&pkg.version?))))
+                        .collect()
+                })
+                .map_err(|e| e.to_string())
+        } else {
+            toml::from_str::<TomlLock>(&content)
+                .map(|lock| lock.package.into_iter().map(|pkg| (pkg.name, pkg.version)).collect())
+                .map_err(|e| e.to_string())
+        };
+
+        match packages {
+            Ok(packages) => {
+                for (name, version) in packages {
+                    locked.entry(normalize_name(&name)).or_insert(version);
+                }
+            }
+            Err(e) => log::warn!("Failed to parse {}: {} - {}", file.name, file.fp, e),
+        }
+    }
+
+    locked
+}
+
+fn is_requirements(name: &str) -> bool {
+    name == "requirements.txt"
+        || (name.starts_with("requirements")
+            && name.ends_with(".txt")
+            && name[12..].starts_with(['-', '_', '.']))
+}
+
+/// Path included by `-r file` or `--requirement=file`.
+fn requirements_include(line: &str) -> Option<&str> {
+    for flag in ["--requirement", "-r"] {
+        if let Some(rest) = line.strip_prefix(flag) {
+            let rest = rest.strip_prefix('=').unwrap_or(rest).trim();
+            if !rest.is_empty() {
+                return Some(rest);
+            }
+        }
+    }
+    None
+}
+
+fn strip_comment(line: &str) -> &str {
+    if line.trim_start().starts_with('#') {
+        return "";
+    }
+    // A # not preceded by a space is part of an url, e.g: #egg=name
+    match line.find(" #").or_else(|| line.find("\t#")) {
+        Some(idx) => &line[..idx],
+        None => line,
+    }
+}
+
+/// Name as compared by pip (PEP 503): case insensitive, and `-`, `_`, `.` are equivalent.
+fn normalize_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.trim().chars() {
+        if c == '-' || c == '_' || c == '.' {
+            if !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+        }
+    }
+    normalized
+}
+
+fn clean_version(version: &str) -> String {
+    let version = version.trim();
+    if version.is_empty() || version == "*" {
+        return "latest".to_string();
+    }
+    match version.strip_prefix("==") {
+        Some(pinned) if !pinned.starts_with('=') && !pinned.contains(',') => pinned.to_string(),
+        _ => version.to_string(),
+    }
+}
+
+/// Parse a PEP 508 requirement, e.g: `requests[socks]>=2.8,<3 ; python_version < "3.8"`
+fn parse_requirement(requirement: &str) -> Option<(String, String)> {
+    // Environment markers only restrict where it gets installed
+    let requirement = requirement.split(';').next()?.trim();
+
+    let name_end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .unwrap_or(requirement.len());
+    let (name, rest) = requirement.split_at(name_end);
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut rest = rest.trim_start();
+    if rest.starts_with('[') {
+        rest = rest.split_once(']')?.1.trim_start();
+    }
+
+    if let Some(url) = rest.strip_prefix('@') {
+        return Some((name.to_string(), url.trim().to_string()));
+    }
+
+    // A bare url or path, we can't know the name without downloading it
+    if !rest.is_empty() && !rest.starts_with(['<', '>', '=', '!', '~', '(']) {
+        return None;
+    }
+
+    let version: String =
+        rest.chars().filter(|c| !c.is_whitespace() && *c != '(' && *c != ')').collect();
+    Some((name.to_string(), clean_version(&version)))
+}
+
+/// Minimal INI parser, enough for setup.cfg: sections, `key = value` and indented continuations.
+fn parse_ini(content: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut section = String::new();
+    let mut key: Option<String> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+            continue;
+        }
+
+        if line.starts_with([' ', '\t']) {
+            if let Some(ref key) = key {
+                let value =
+                    sections.entry(section.clone()).or_default().entry(key.clone()).or_default();
+                value.push('\n');
+                value.push_str(trimmed);
+            }
+            continue;
+        }
+
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            section = trimmed[1..trimmed.len() - 1].trim().to_string();
+            key = None;
+            continue;
+        }
+
+        if let Some((k, v)) = trimmed.split_once(['=', ':']) {
+            let k = k.trim().to_string();
+            sections
+                .entry(section.clone())
+                .or_default()
+                .insert(k.clone(), v.trim().to_string());
+            key = Some(k);
+        }
+    }
+
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::fake::FakeProvider;
+
+    fn dep(name: &str, resolved: &str, declared: &str, scope: &str) -> Vec<String> {
+        dependency("python", name, resolved, declared, scope)
+    }
+
+    fn provider(files: &[(&str, &str)]) -> FakeProvider {
+        let mut paths: HashMap<String, Vec<String>> = HashMap::new();
+        for (fp, _) in files {
+            let path = Path::new(fp);
+            paths
+                .entry(path.parent().unwrap().to_string_lossy().to_string())
+                .or_default()
+                .push(path.file_name().unwrap().to_string_lossy().to_string());
+        }
+        FakeProvider::new(
+            paths,
+            files
+                .iter()
+                .map(|(fp, content)| (fp.to_string(), content.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_detect_python_requirements() {
+        let provider = provider(&[
+            (
+                "/app/requirements.txt",
+                "# Web\nDjango==4.2.1\nrequests[socks] >= 2.8, < 3 ; python_version >= \"3.8\"\n\
+                 psycopg2-binary \\\n    ==2.9.6\n--index-url https://pypi.org/simple\n\
+                 -e git+https://github.com/acme/lib.git#egg=lib\n./local-package\n\
+                 -r ../common/requirements.txt\n",
+            ),
+            (
+                "/app/requirements-dev.txt",
+                "-r requirements.txt\npytest  # tests\nFlask_SQLAlchemy @ https://example.com/f.zip\n",
+            ),
+            ("/common/requirements.txt", "six\ndjango==1.0.0\n"),
+        ]);
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let result =
+            detect_python_component(&provider.list_dir("/app"), &provider, &registry).unwrap();
+
+        assert_eq!(result.name, "app");
+        assert!(result.path.contains("/app/requirements.txt"));
+        assert!(result.path.contains("/app/requirements-dev.txt"));
+        assert_eq!(
+            result.dependencies,
+            vec![
+                dep("django", "", "4.2.1", ""),
+                dep("flask-sqlalchemy", "", "https://example.com/f.zip", ""),
+                dep("psycopg2-binary", "", "2.9.6", ""),
+                dep("pytest", "", "latest", ""),
+                dep("requests", "", ">=2.8,<3", ""),
+                dep("six", "", "latest", ""),
+            ]
+        );
+        assert!(result.techs.contains("postgresql"));
+    }
+
+    #[test]
+    fn test_detect_python_poetry() {
+        let provider = provider(&[
+            (
+                "/api/pyproject.toml",
+                r#"
+[tool.poetry]
+name = "acme-api"
+
+[tool.poetry.dependencies]
+python = "^3.10"
+asyncpg = "^0.27"
+fastapi = { version = "0.95.*", extras = ["all"] }
+shared = { path = "../shared", develop = true }
+lib = { git = "https://github.com/acme/lib.git", tag = "v1" }
+
+[tool.poetry.group.dev.dependencies]
+pytest = "*"
+"#,
+            ),
+            (
+                "/api/poetry.lock",
+                r#"
+[[package]]
+name = "asyncpg"
+version = "0.27.0"
+
+[[package]]
+name = "shared"
+version = "0.1.0"
+
+[[package]]
+name = "pytest"
+version = "7.3.1"
+"#,
+            ),
+        ]);
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let result =
+            detect_python_component(&provider.list_dir("/api"), &provider, &registry).unwrap();
+
+        assert_eq!(result.name, "acme-api");
+        assert_eq!(
+            result.dependencies,
+            vec![
+                dep("asyncpg", "0.27.0", "^0.27", ""),
+                dep("fastapi", "", "0.95.*", ""),
+                dep("lib", "", "git:https://github.com/acme/lib.git#v1", ""),
+                dep("pytest", "7.3.1", "latest", "dev"),
+                dep("shared", "path:../shared", "path:../shared", ""),
+            ]
+        );
+        assert!(result.reason.contains("python: ^3.10"));
+        assert!(result.techs.contains("postgresql"));
+    }
+
+    #[test]
+    fn test_detect_python_pep621() {
+        let provider = provider(&[(
+            "/svc/pyproject.toml",
+            r#"
+[project]
+name = "svc"
+requires-python = ">=3.9"
+dependencies = ["httpx>=0.24", "psycopg[binary]==3.1.9; sys_platform != 'win32'"]
+
+[project.optional-dependencies]
+docs = ["mkdocs"]
+
+[tool.pdm.dev-dependencies]
+lint = ["ruff"]
+
+[dependency-groups]
+test = ["pytest>=7", { include-group = "lint" }]
+"#,
+        )]);
+
+        let result =
+            detect_python_component(&provider.list_dir("/svc"), &provider, &RuleRegistry::new())
+                .unwrap();
+
+        assert_eq!(result.name, "svc");
+        assert_eq!(
+            result.dependencies,
+            vec![
+                dep("httpx", "", ">=0.24", ""),
+                dep("mkdocs", "", "latest", "optional"),
+                dep("psycopg", "", "3.1.9", ""),
+                dep("pytest", "", ">=7", "dev"),
+                dep("ruff", "", "latest", "dev"),
+            ]
+        );
+        assert!(result.reason.contains("python: >=3.9"));
+    }
+
+    #[test]
+    fn test_detect_python_pipfile_and_setup_cfg() {
+        let provider = provider(&[
+            (
+                "/worker/Pipfile",
+                "[packages]\ncelery = \"*\"\nredis = { version = \">=4\" }\n\n\
+                 [dev-packages]\nblack = \"==23.1\"\n\n[requires]\npython_version = \"3.11\"\n",
+            ),
+            (
+                "/worker/Pipfile.lock",
+                r#"{"default": {"celery": {"version": "==5.2.7"}}, "develop": {}}"#,
+            ),
+            (
+                "/worker/setup.cfg",
+                "[metadata]\nname = worker\n\n[options]\ninstall_requires =\n    \
+                 kombu>=5\n    # comment\n    celery\n\n[options.extras_require]\nsentry = sentry-sdk\n",
+            ),
+        ]);
+
+        let result = detect_python_component(
+            &provider.list_dir("/worker"),
+            &provider,
+            &RuleRegistry::new(),
+        )
+        .unwrap();
+
+        assert_eq!(result.name, "worker");
+        assert_eq!(result.path.len(), 2);
+        assert_eq!(
+            result.dependencies,
+            vec![
+                dep("black", "", "23.1", "dev"),
+                dep("celery", "5.2.7", "latest", ""),
+                dep("kombu", "", ">=5", ""),
+                dep("redis", "", ">=4", ""),
+                dep("sentry-sdk", "", "latest", "optional"),
+            ]
+        );
+        assert!(result.reason.contains("python: 3.11"));
+    }
+
+    #[test]
+    fn test_detect_python_nothing() {
+        let provider = provider(&[
+            ("/lib/setup.py", "from setuptools import setup\n"),
+            ("/lib/poetry.lock", ""),
+        ]);
+
+        let res =
+            detect_python_component(&provider.list_dir("/lib"), &provider, &RuleRegistry::new());
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_parse_requirement() {
+        assert_eq!(
+            parse_requirement("Django (>=3.2, <4)"),
+            Some(("Django".to_string(), ">=3.2,<4".to_string()))
+        );
+        assert_eq!(
+            parse_requirement("numpy===1.0"),
+            Some(("numpy".to_string(), "===1.0".to_string()))
+        );
+        assert_eq!(parse_requirement("https://example.com/pkg.whl"), None);
+        assert_eq!(parse_requirement("# nothing"), None);
+        assert_eq!(normalize_name("Flask__SQL.Alchemy"), "flask-sql-alchemy");
+    }
+}
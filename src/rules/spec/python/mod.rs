@@ -0,0 +1,48 @@
+pub mod component;
+use component::detect_python_component;
+
+use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
+use crate::types::rule::{Rule, RuleDependency, RuleFiles};
+
+pub fn register_python(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("python"),
+            name: String::from("Python"),
+            r#type: String::from("language"),
+            files: Some(RuleFiles::FilesArray {
+                files: vec![
+                    String::from("requirements.txt"),
+                    String::from("pyproject.toml"),
+                    String::from("Pipfile"),
+                    String::from("setup.cfg"),
+                    String::from("setup.py"),
+                    String::from("poetry.lock"),
+                    String::from("Pipfile.lock"),
+                    String::from("pdm.lock"),
+                ],
+            }),
+            dependencies: Some(vec![
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("python")),
+                    example: Some(String::from("python:3.12-slim")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("circleci/python")),
+                    example: Some(String::from("circleci/python:3.9")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("cimg/python")),
+                    example: Some(String::from("cimg/python:3.11")),
+                },
+            ]),
+            detect: Some(vec![detect_python_component]),
+            ..Default::default()
+        },
+    )
+}
@@ -0,0 +1,649 @@
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::{
+    payload::payload::{dependency, Payload},
+    provider::base::{folder_name, BaseProvider, ProviderFile},
+    rules::{match_dependencies::match_dependencies, registry::RuleRegistry},
+};
+
+const GEMFILE: &str = "Gemfile";
+const LOCKFILE: &str = "Gemfile.lock";
+
+lazy_static::lazy_static! {
+    static ref BLOCK_REG: Regex = Regex::new(r"\s*\bdo\s*(\|[^|]*\|)?$").unwrap();
+    static ref OPTION_REG: Regex =
+        Regex::new(r#"^(?::|["'])?(\w+)["']?(?:\s*=>\s*|:\s+)(.*)$"#).unwrap();
+    static ref GEMSPEC_NAME_REG: Regex = Regex::new(r#"\.name\s*=\s*["']([^"']+)["']"#).unwrap();
+}
+
+/// Keywords opening a block closed by `end`, when they start a line.
+const BLOCK_KEYWORDS: [&str; 9] = [
+    "if", "unless", "case", "begin", "while", "until", "def", "class", "module",
+];
+
+#[derive(Debug, Default, PartialEq)]
+struct Gem {
+    name: String,
+    requirements: Vec<String>,
+    groups: Vec<String>,
+    source: Option<String>,
+    /// What `Bundler.require` loads instead of the gem name, `false` for nothing
+    require: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Gemfile {
+    ruby: Option<String>,
+    gems: Vec<Gem>,
+}
+
+#[derive(Debug, Default)]
+struct GemfileLock {
+    ruby: Option<String>,
+    specs: HashMap<String, String>,
+    /// Gems of the Gemfile, with their requirements
+    dependencies: Vec<(String, Vec<String>)>,
+}
+
+/// What a `do ... end` block applies to the gems declared inside.
+#[derive(Debug, Default, Clone)]
+struct Block {
+    groups: Vec<String>,
+    source: Option<String>,
+}
+
+enum Arg {
+    Positional(String),
+    Option(String, String),
+}
+
+pub fn detect_ruby_component(
+    files: &Vec<ProviderFile>,
+    provider: &dyn BaseProvider,
+    registry: &RuleRegistry,
+) -> Result<Payload, bool> {
+    let mut gemfile: Option<(&ProviderFile, Gemfile)> = None;
+    let mut lock: Option<(&ProviderFile, GemfileLock)> = None;
+    let mut gemspec: Option<&ProviderFile> = None;
+
+    for file in files {
+        match file.name.as_str() {
+            GEMFILE => {
+                if let Some(content) = provider.open(&file.fp) {
+                    gemfile = Some((file, parse_gemfile(&content)));
+                }
+            }
+            LOCKFILE => {
+                if let Some(content) = provider.open(&file.fp) {
+                    lock = Some((file, parse_gemfile_lock(&content)));
+                }
+            }
+            name if name.ends_with(".gemspec") => gemspec = Some(file),
+            _ => {}
+        }
+    }
+
+    let first = match (&gemfile, &lock) {
+        (Some((file, _)), _) | (None, Some((file, _))) => *file,
+        (None, None) => return Err(false),
+    };
+
+    let name = gemspec
+        .and_then(|file| gemspec_name(file, provider))
+        .unwrap_or_else(|| folder_name(&first.fp, provider));
+
+    let mut pl = Payload::new(&name, &first.fp);
+    let lock = match lock {
+        Some((file, lock)) => {
+            pl.path.insert(file.fp.clone());
+            lock
+        }
+        None => GemfileLock::default(),
+    };
+
+    // Without a Gemfile, the lockfile still knows what was asked for
+    let (gems, ruby) = match gemfile {
+        Some((_, gemfile)) => (gemfile.gems, gemfile.ruby.or(lock.ruby.clone())),
+        None => (
+            lock.dependencies
+                .iter()
+                .map(|(name, requirements)| Gem {
+                    name: name.clone(),
+                    requirements: requirements.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+            lock.ruby.clone(),
+        ),
+    };
+
+    let mut deps_flatten: Vec<Vec<String>> = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+    for gem in gems {
+        if names.contains(&gem.name) {
+            continue;
+        }
+        let mut dep = dependency(
+            "ruby",
+            &gem.name,
+            &gem.resolved(&lock.specs),
+            &gem.declared(),
+            gem.scope(),
+        );
+        dep.extend(gem.groups());
+        dep.extend(gem.require.as_ref().map(|require| format!("require:{}", require)));
+        deps_flatten.push(dep);
+        names.push(gem.name);
+    }
+
+    if let Some(ruby) = ruby {
+        pl.reason.insert(format!("ruby: {}", ruby));
+    }
+
+    let techs = match_dependencies(&names, "ruby", registry);
+
+    pl.add_techs(&techs, registry);
+    pl.dependencies = deps_flatten;
+
+    Ok(pl)
+}
+
+impl Gem {
+    fn resolved(&self, specs: &HashMap<String, String>) -> String {
+        match &self.source {
+            Some(source) if source.starts_with("path:") => source.clone(),
+            _ => specs.get(&self.name).cloned().unwrap_or_default(),
+        }
+    }
+
+    fn declared(&self) -> String {
+        if let Some(ref source) = self.source {
+            return source.clone();
+        }
+        match self.requirements.as_slice() {
+            [] => "latest".to_string(),
+            [exact] if exact.starts_with('=') => exact.trim_start_matches('=').trim().to_string(),
+            requirements => requirements.join(", "),
+        }
+    }
+
+    /// Gems only installed for development or tests are `dev`
+    fn scope(&self) -> &str {
+        let dev = |group: &String| group == "development" || group == "test";
+        if !self.groups.is_empty() && self.groups.iter().all(dev) {
+            return "dev";
+        }
+        ""
+    }
+
+    /// Gems outside of the default group are only installed on demand, e.g: `groups:production`
+    fn groups(&self) -> Option<String> {
+        // Gems only known from the lockfile have no group
+        if self.groups.is_empty() || self.groups.iter().any(|group| group == "default") {
+            return None;
+        }
+        if self.scope() == "dev" {
+            return None;
+        }
+        Some(format!("groups:{}", self.groups.join(",")))
+    }
+}
+
+fn gemspec_name(file: &ProviderFile, provider: &dyn BaseProvider) -> Option<String> {
+    let content = provider.open(&file.fp).unwrap_or_default();
+    match GEMSPEC_NAME_REG.captures(&content) {
+        Some(caps) => Some(caps[1].to_string()),
+        None => file.name.strip_suffix(".gemspec").map(|s| s.to_string()),
+    }
+}
+
+fn parse_gemfile(content: &str) -> Gemfile {
+    let mut gemfile = Gemfile::default();
+    let mut blocks: Vec<Block> = Vec::new();
+
+    for line in content.lines() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line == "end" || line.starts_with("end ") || line.starts_with("end.") {
+            blocks.pop();
+            continue;
+        }
+
+        let (line, opens_block) = match BLOCK_REG.find(line) {
+            Some(m) => (&line[..m.start()], true),
+            None => (line, false),
+        };
+        let (keyword, rest) =
+            line.split_once(|c: char| c.is_whitespace() || c == '(').unwrap_or((line, ""));
+        let rest = rest.trim();
+        let rest = rest.strip_suffix(')').unwrap_or(rest);
+        let args: Vec<Arg> = split_args(rest).into_iter().map(|a| parse_arg(&a)).collect();
+
+        if opens_block {
+            let mut block = blocks.last().cloned().unwrap_or_default();
+            match keyword {
+                "group" => block.groups.extend(positionals(&args)),
+                "git" | "github" | "path" => block.source = gem_source(keyword, &args),
+                _ => {}
+            }
+            blocks.push(block);
+            continue;
+        }
+        if BLOCK_KEYWORDS.contains(&keyword) {
+            blocks.push(blocks.last().cloned().unwrap_or_default());
+            continue;
+        }
+
+        match keyword {
+            "gem" => {
+                let block = blocks.last().cloned().unwrap_or_default();
+                if let Some(gem) = parse_gem(&args, block) {
+                    gemfile.gems.push(gem);
+                }
+            }
+            "ruby" => {
+                if let Some(version) = positionals(&args).first() {
+                    gemfile.ruby = Some(version.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    gemfile
+}
+
+fn parse_gem(args: &[Arg], block: Block) -> Option<Gem> {
+    let mut positionals = positionals(args).into_iter();
+    let mut gem = Gem {
+        name: positionals.next()?,
+        requirements: positionals.collect(),
+        groups: block.groups,
+        source: block.source,
+        require: None,
+    };
+
+    for arg in args {
+        let Arg::Option(key, value) = arg else {
+            continue;
+        };
+        match key.as_str() {
+            "group" | "groups" => gem.groups.extend(list_values(value)),
+            "git" | "github" | "path" => gem.source = gem_source(key, args),
+            "require" => gem.require = Some(list_values(value).join(",")),
+            _ => {}
+        }
+    }
+
+    if gem.groups.is_empty() {
+        gem.groups.push("default".to_string());
+    }
+
+    Some(gem)
+}
+
+/// Where a gem comes from when it's not from the rubygems server.
+fn gem_source(kind: &str, args: &[Arg]) -> Option<String> {
+    let mut location = None;
+    let mut reference = None;
+    for arg in args {
+        match arg {
+            Arg::Positional(value) if location.is_none() => location = Some(value.clone()),
+            Arg::Option(key, value) if key == kind => location = Some(unquote(value)),
+            Arg::Option(key, value) if ["branch", "ref", "tag"].contains(&key.as_str()) => {
+                reference = Some(unquote(value))
+            }
+            _ => {}
+        }
+    }
+
+    let location = location?;
+    match kind {
+        "path" => Some(format!("path:{}", location)),
+        "github" => Some(format!(
+            "git:https://github.com/{}.git#{}",
+            location,
+            reference.as_deref().unwrap_or("latest")
+        )),
+        _ => Some(format!(
+            "git:{}#{}",
+            location,
+            reference.as_deref().unwrap_or("latest")
+        )),
+    }
+}
+
+fn parse_gemfile_lock(content: &str) -> GemfileLock {
+    let mut lock = GemfileLock::default();
+    let mut section = "";
+
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if !line.starts_with(' ') {
+            section = line.trim();
+            continue;
+        }
+
+        match section {
+            // Resolved gems are indented by 4, their own dependencies by 6
+            "GEM" | "GIT" | "PATH" if line.starts_with("    ") && !line.starts_with("     ") => {
+                let Some((name, version)) = line.trim().split_once(' ') else {
+                    continue;
+                };
+                let version = version.trim_start_matches('(').trim_end_matches(')');
+                // Drop the platform, e.g: 1.15.0-x86_64-linux
+                let version = version.split('-').next().unwrap_or(version);
+                lock.specs.entry(name.to_string()).or_insert(version.to_string());
+            }
+            "DEPENDENCIES" => {
+                // e.g: `pg (>= 0.18, < 2.0)`, or `devise!` when not from rubygems
+                let line = line.trim();
+                let name = line.split([' ', '!']).next().unwrap_or_default();
+                if name.is_empty() {
+                    continue;
+                }
+                let requirements = match line.split_once('(') {
+                    Some((_, rest)) => {
+                        rest.trim_end_matches(')').split(", ").map(String::from).collect()
+                    }
+                    None => Vec::new(),
+                };
+                lock.dependencies.push((name.to_string(), requirements));
+            }
+            "RUBY VERSION" => {
+                if let Some(version) = line.trim().strip_prefix("ruby ") {
+                    // Drop the patch level, e.g: 3.2.2p53
+                    lock.ruby = version.split('p').next().map(|v| v.trim().to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    lock
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    for (idx, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '#') => return &line[..idx],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Split arguments on commas, ignoring the ones in strings or lists.
+fn split_args(args: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut depth = 0;
+
+    for c in args.chars() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '[') | (None, '{') => depth += 1,
+            (None, ']') | (None, '}') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                result.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+
+    if !current.trim().is_empty() {
+        result.push(current.trim().to_string());
+    }
+    result
+}
+
+fn parse_arg(arg: &str) -> Arg {
+    match OPTION_REG.captures(arg) {
+        Some(caps) => Arg::Option(caps[1].to_string(), caps[2].trim().to_string()),
+        None => Arg::Positional(unquote(arg)),
+    }
+}
+
+fn positionals(args: &[Arg]) -> Vec<String> {
+    args.iter()
+        .filter_map(|arg| match arg {
+            Arg::Positional(value) => Some(value.clone()),
+            Arg::Option(..) => None,
+        })
+        .collect()
+}
+
+/// `"a"`, `'a'` and `:a` are all `a`.
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    let value = value.strip_prefix(':').unwrap_or(value);
+    value.trim_matches(|c| c == '"' || c == '\'').to_string()
+}
+
+fn list_values(value: &str) -> Vec<String> {
+    let value = value.trim().trim_start_matches('[').trim_end_matches(']');
+    split_args(value).iter().map(|v| unquote(v)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{fake::FakeProvider, fs::FsProvider};
+
+    fn dep(name: &str, resolved: &str, declared: &str) -> Vec<String> {
+        dependency("ruby", name, resolved, declared, "")
+    }
+
+    const GEMFILE_CONTENT: &str = r#"
+source "https://rubygems.org"
+git_source(:github) { |repo| "https://github.com/#{repo}.git" }
+
+ruby "3.2.2"
+
+gem "rails", "~> 7.0.4"
+gem 'pg', '>= 0.18', '< 2.0'
+gem "puma", require: false # web server
+gem "sidekiq", :require => "sidekiq/web", :group => [:default, :worker]
+gem "internal", path: "../internal"
+gem "devise", github: "heartcombo/devise", branch: "main"
+gem("bootsnap", ">= 1.4.4")
+
+group :development, :test do
+  gem "rspec-rails"
+  if ENV["DEBUGGER"]
+    gem "debug", platforms: %i[ mri mingw x64_mingw ]
+  end
+end
+
+gem "rubocop", group: :development
+"#;
+
+    const LOCK_CONTENT: &str = r#"
+GEM
+  remote: https://rubygems.org/
+  specs:
+    nokogiri (1.15.0-x86_64-linux)
+      racc (~> 1.4)
+    pg (1.5.3)
+    rails (7.0.4.3)
+      actioncable (= 7.0.4.3)
+
+PLATFORMS
+  x86_64-linux
+
+DEPENDENCIES
+  pg (>= 0.18, < 2.0)
+  rails (~> 7.0.4)
+
+RUBY VERSION
+   ruby 3.2.2p53
+
+BUNDLED WITH
+   2.4.10
+"#;
+
+    #[test]
+    fn test_parse_gemfile() {
+        let gemfile = parse_gemfile(GEMFILE_CONTENT);
+
+        assert_eq!(gemfile.ruby.as_deref(), Some("3.2.2"));
+        assert_eq!(gemfile.gems.len(), 10);
+        assert_eq!(
+            gemfile.gems[1],
+            Gem {
+                name: "pg".to_string(),
+                requirements: vec![">= 0.18".to_string(), "< 2.0".to_string()],
+                groups: vec!["default".to_string()],
+                ..Default::default()
+            }
+        );
+        assert_eq!(gemfile.gems[2].groups, vec!["default"]);
+        assert_eq!(gemfile.gems[2].require.as_deref(), Some("false"));
+        assert_eq!(gemfile.gems[3].groups, vec!["default", "worker"]);
+        assert_eq!(gemfile.gems[3].require.as_deref(), Some("sidekiq/web"));
+        assert_eq!(gemfile.gems[0].require, None);
+        assert_eq!(gemfile.gems[6].requirements, vec![">= 1.4.4"]);
+        assert_eq!(gemfile.gems[7].groups, vec!["development", "test"]);
+        assert_eq!(gemfile.gems[8].name, "debug");
+        assert_eq!(gemfile.gems[8].groups, vec!["development", "test"]);
+        assert_eq!(gemfile.gems[9].groups, vec!["development"]);
+    }
+
+    #[test]
+    fn test_gem_scope() {
+        let gem = |groups: &[&str]| Gem {
+            groups: groups.iter().map(|g| g.to_string()).collect(),
+            ..Default::default()
+        };
+
+        assert_eq!(gem(&[]).scope(), "");
+        assert_eq!(gem(&[]).groups(), None);
+        assert_eq!(gem(&["default", "worker"]).scope(), "");
+        assert_eq!(gem(&["default", "worker"]).groups(), None);
+        assert_eq!(gem(&["development", "test"]).scope(), "dev");
+        assert_eq!(gem(&["development", "test"]).groups(), None);
+        assert_eq!(gem(&["staging", "production"]).scope(), "");
+        assert_eq!(
+            gem(&["staging", "production"]).groups().as_deref(),
+            Some("groups:staging,production")
+        );
+    }
+
+    #[test]
+    fn test_detect_ruby_component() {
+        let provider = FakeProvider::new(
+            HashMap::from_iter([(
+                "/app".to_string(),
+                vec![
+                    "Gemfile".to_string(),
+                    "Gemfile.lock".to_string(),
+                    "shop.gemspec".to_string(),
+                ],
+            )]),
+            HashMap::from_iter([
+                ("/app/Gemfile".to_string(), GEMFILE_CONTENT.to_string()),
+                ("/app/Gemfile.lock".to_string(), LOCK_CONTENT.to_string()),
+                (
+                    "/app/shop.gemspec".to_string(),
+                    "Gem::Specification.new do |spec|\n  spec.name = \"acme-shop\"\nend\n"
+                        .to_string(),
+                ),
+            ]),
+        );
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let result =
+            detect_ruby_component(&provider.list_dir("/app"), &provider, &registry).unwrap();
+
+        assert_eq!(result.name, "acme-shop");
+        assert_eq!(result.path.len(), 2);
+
+        let deps = &result.dependencies;
+        assert_eq!(deps.len(), 10);
+        assert!(deps.contains(&dep("rails", "7.0.4.3", "~> 7.0.4")));
+        assert!(deps.contains(&dep("pg", "1.5.3", ">= 0.18, < 2.0")));
+        let mut puma = dep("puma", "", "latest");
+        puma.push("require:false".to_string());
+        assert!(deps.contains(&puma));
+        assert!(deps.contains(&dep("bootsnap", "", ">= 1.4.4")));
+        assert!(deps.contains(&dep("internal", "path:../internal", "path:../internal")));
+        assert!(deps.contains(&dep(
+            "devise",
+            "",
+            "git:https://github.com/heartcombo/devise.git#main"
+        )));
+        assert!(result.reason.contains("ruby: 3.2.2"));
+        assert!(deps.contains(&dependency("ruby", "rspec-rails", "", "latest", "dev")));
+        assert!(deps.contains(&dependency("ruby", "rubocop", "", "latest", "dev")));
+        let mut sidekiq = dep("sidekiq", "", "latest");
+        sidekiq.push("require:sidekiq/web".to_string());
+        assert!(deps.contains(&sidekiq));
+
+        assert!(result.techs.contains("postgresql"));
+    }
+
+    #[test]
+    fn test_detect_ruby_lockfile_only() {
+        let provider = FakeProvider::new(
+            HashMap::from_iter([("/app".to_string(), vec!["Gemfile.lock".to_string()])]),
+            HashMap::from_iter([("/app/Gemfile.lock".to_string(), LOCK_CONTENT.to_string())]),
+        );
+
+        let result =
+            detect_ruby_component(&provider.list_dir("/app"), &provider, &RuleRegistry::new())
+                .unwrap();
+
+        assert_eq!(result.name, "app");
+        assert_eq!(
+            result.dependencies,
+            vec![
+                dep("pg", "1.5.3", ">= 0.18, < 2.0"),
+                dep("rails", "7.0.4.3", "~> 7.0.4")
+            ]
+        );
+        assert!(result.reason.contains("ruby: 3.2.2"));
+    }
+
+    #[test]
+    fn test_detect_ruby_fixture() {
+        let provider = FsProvider::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/__fixtures__/ruby"
+        ))
+        .unwrap();
+        let registry = RuleRegistry::with_defaults().unwrap();
+
+        let files = provider.list_dir(&provider.base_path());
+        let result = detect_ruby_component(&files, &provider, &registry).unwrap();
+
+        assert_eq!(result.name, "ruby");
+        let mut puma = dep("puma", "", "6.3.0");
+        puma.push("require:false".to_string());
+        assert_eq!(result.dependencies, vec![dep("rails", "", "6.1.7.4"), puma]);
+    }
+
+    #[test]
+    fn test_detect_ruby_nothing() {
+        let provider = FakeProvider::new(
+            HashMap::from_iter([("/app".to_string(), vec!["app.gemspec".to_string()])]),
+            HashMap::new(),
+        );
+
+        let res =
+            detect_ruby_component(&provider.list_dir("/app"), &provider, &RuleRegistry::new());
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,43 @@
+pub mod component;
+use component::detect_ruby_component;
+
+use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
+use crate::types::rule::{Rule, RuleDependency, RuleFiles};
+
+pub fn register_ruby(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("ruby"),
+            name: String::from("Ruby"),
+            r#type: String::from("language"),
+            files: Some(RuleFiles::FilesArray {
+                files: vec![
+                    String::from("Gemfile"),
+                    String::from("Gemfile.lock"),
+                    String::from(".ruby-version"),
+                ],
+            }),
+            dependencies: Some(vec![
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("ruby")),
+                    example: Some(String::from("ruby:3.2-alpine")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("circleci/ruby")),
+                    example: Some(String::from("circleci/ruby:2.7")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("cimg/ruby")),
+                    example: Some(String::from("cimg/ruby:3.2")),
+                },
+            ]),
+            detect: Some(vec![detect_ruby_component]),
+            ..Default::default()
+        },
+    )
+}
@@ -1,23 +1,27 @@
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::path::Path;
 
+use super::lockfile::{find_lockfile, Lockfile};
+use super::workspace::find_workspace;
 use crate::{
-    payload::payload::Payload,
-    provider::base::{BaseProvider, ProviderFile},
-    rules::match_dependencies::match_dependencies,
+    payload::payload::{dependency, Payload},
+    provider::base::{normalize_path, relative_path, BaseProvider, ProviderFile},
+    rules::{match_dependencies::match_dependencies, registry::RuleRegistry},
 };
 
 const FILES: [&str; 1] = ["Cargo.toml"];
 
+type Dependencies = BTreeMap<String, Dependency>;
+
 #[derive(Debug, Deserialize)]
-struct CargoToml {
+pub(super) struct CargoToml {
     package: Option<Package>,
-    dependencies: Option<HashMap<String, Dependency>>,
-    #[serde(rename = "dev-dependencies")]
-    dev_dependencies: Option<HashMap<String, Dependency>>,
-    #[serde(rename = "build-dependencies")]
-    build_dependencies: Option<HashMap<String, Dependency>>,
-    workspace: Option<Workspace>,
+    #[serde(flatten)]
+    tables: Tables,
+    /// `[target.'cfg(unix)'.dependencies]`
+    target: Option<BTreeMap<String, Tables>>,
+    pub(super) workspace: Option<Workspace>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -26,27 +30,111 @@ struct Package {
     // Add other fields as needed
 }
 
+/// Dependencies of a crate, or of a crate on a given platform
 #[derive(Debug, Deserialize)]
-struct Workspace {
-    dependencies: Option<HashMap<String, Dependency>>,
+struct Tables {
+    dependencies: Option<Dependencies>,
+    #[serde(rename = "dev-dependencies")]
+    dev_dependencies: Option<Dependencies>,
+    #[serde(rename = "build-dependencies")]
+    build_dependencies: Option<Dependencies>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
+pub(super) struct Workspace {
+    /// Globs of the member folders, relative to the workspace root
+    #[serde(default)]
+    pub(super) members: Vec<String>,
+    #[serde(default)]
+    pub(super) exclude: Vec<String>,
+    dependencies: Option<Dependencies>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
 #[serde(untagged)]
 enum Dependency {
     Simple(String),
-    Detailed {
-        version: Option<String>,
-        path: Option<String>,
-        git: Option<String>,
-        branch: Option<String>,
-        rev: Option<String>,
-    },
+    Detailed(DetailedDependency),
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct DetailedDependency {
+    version: Option<String>,
+    path: Option<String>,
+    git: Option<String>,
+    branch: Option<String>,
+    tag: Option<String>,
+    rev: Option<String>,
+    /// Name of the crate, when the dependency is renamed
+    package: Option<String>,
+    /// Inherited from `[workspace.dependencies]`
+    #[serde(default)]
+    workspace: bool,
+    #[serde(default)]
+    features: Vec<String>,
+    #[serde(default)]
+    optional: bool,
 }
 
+impl Dependency {
+    fn into_detailed(self) -> DetailedDependency {
+        match self {
+            Dependency::Simple(version) => DetailedDependency {
+                version: Some(version),
+                ..Default::default()
+            },
+            Dependency::Detailed(detailed) => detailed,
+        }
+    }
+}
+
+impl DetailedDependency {
+    /// Version as written in the manifest, or its source when it is not constrained
+    fn declared(&self) -> String {
+        if let Some(path) = &self.path {
+            return self.version.clone().unwrap_or_else(|| format!("path:{}", path));
+        }
+        if let Some(git_str) = &self.git {
+            let suffix = [&self.branch, &self.tag, &self.rev]
+                .into_iter()
+                .find_map(|s| s.as_deref())
+                .unwrap_or("latest");
+            format!("git:{}#{}", git_str, suffix)
+        } else {
+            self.version.clone().unwrap_or_else(|| "latest".to_string())
+        }
+    }
+
+    /// `dep = { workspace = true }` completed by its declaration in the workspace,
+    /// local paths are relative to the workspace root and must be rebased on the member.
+    fn inherit(self, inherited: &Dependency, root: &Path, member: &Path) -> DetailedDependency {
+        let mut dep = inherited.clone().into_detailed();
+        dep.path = dep.path.map(|path| {
+            let path = relative_path(member, &normalize_path(&root.join(path)));
+            path.to_string_lossy().to_string()
+        });
+        // Features are additive, optional can only be set by the member
+        for feature in self.features {
+            if !dep.features.contains(&feature) {
+                dep.features.push(feature);
+            }
+        }
+        dep.optional = self.optional;
+        dep
+    }
+}
+
+/// Dependencies are resolved with the Cargo.lock of the crate or of its workspace,
+/// the features they enable are kept as a `features:<a,b>` attribute.
+///
+/// Local dependencies resolve to `path:<folder>`, and become edges between the crates of
+/// a workspace.
+///
+/// Transitive crates are only listed with `RuleRegistry::transitive`.
 pub fn detect_rust_component(
     files: &Vec<ProviderFile>,
     provider: &dyn BaseProvider,
+    registry: &RuleRegistry,
 ) -> Result<Payload, bool> {
     for file in files {
         if !FILES.contains(&file.name.as_str()) {
@@ -61,78 +149,90 @@ pub fn detect_rust_component(
         let cargo_toml: CargoToml = match toml::from_str(&content) {
             Ok(manifest) => manifest,
             Err(e) => {
-                println!("Failed to parse Cargo.toml: {} - {}", file.fp, e);
+                log::warn!("Failed to parse Cargo.toml: {} - {}", file.fp, e);
                 continue;
             }
         };
 
-        let mut pl = if let Some(package) = cargo_toml.package {
-            Payload::new(&package.name, &file.fp)
-        } else {
-            Payload::new("virtual", &file.fp)
-        };
+        let root = cargo_toml.package.as_ref().map(|package| package.name.as_str());
+        let mut pl = Payload::new(root.unwrap_or("virtual"), &file.fp);
 
-        // Collect all dependencies into a single HashMap
-        let mut deps = HashMap::new();
+        // A workspace member shares the lockfile of its workspace, which is not part of it
+        let lockfile = find_lockfile(&file.fp, provider).map(|(fp, lockfile)| {
+            if Path::new(&fp).parent() == Path::new(&file.fp).parent() {
+                pl.path.insert(fp);
+            }
+            lockfile
+        });
+        let workspace = find_workspace(&file.fp, &cargo_toml, provider);
 
-        if let Some(ref deps_map) = cargo_toml.dependencies {
-            deps.extend(deps_map);
-        }
-        if let Some(ref dev_deps) = cargo_toml.dev_dependencies {
-            deps.extend(dev_deps);
-        }
-        if let Some(ref build_deps) = cargo_toml.build_dependencies {
-            deps.extend(build_deps);
+        // Each kind is kept, a crate can be both a dependency and a dev dependency
+        let kinds = [
+            (dependencies(&cargo_toml, |t| &t.dependencies), None),
+            (
+                dependencies(&cargo_toml, |t| &t.dev_dependencies),
+                Some("dev"),
+            ),
+            (
+                dependencies(&cargo_toml, |t| &t.build_dependencies),
+                Some("build"),
+            ),
+        ];
+        let workspace_deps = cargo_toml
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.dependencies.clone())
+            .unwrap_or_default();
+
+        let member = normalize_path(Path::new(&file.fp).parent().unwrap_or(Path::new("")));
+        let mut names: Vec<String> = Vec::new();
+        let mut deps_flatten: Vec<Vec<String>> = Vec::new();
+        let declared = kinds
+            .into_iter()
+            .flat_map(|(deps, kind)| deps.into_iter().map(move |dep| (dep, kind)))
+            .chain(workspace_deps.into_iter().map(|dep| (dep, None)));
+        for ((key, value), kind) in declared {
+            let mut value = value.into_detailed();
+            if value.workspace {
+                let inherited = workspace.as_ref().and_then(|(root_dir, workspace)| {
+                    let dep = workspace.dependencies.as_ref()?.get(&key)?;
+                    Some((root_dir, dep))
+                });
+                match inherited {
+                    Some((root_dir, dep)) => value = value.inherit(dep, root_dir, &member),
+                    None => log::warn!("Dependency not found in workspace: {} - {}", key, file.fp),
+                }
+            }
+
+            let name = value.package.clone().unwrap_or(key);
+            let dep = crate_dependency(&name, &value, kind, root, lockfile.as_ref());
+            if !names.contains(&name) {
+                names.push(name);
+            }
+            if !deps_flatten.contains(&dep) {
+                deps_flatten.push(dep);
+            }
         }
-        if let Some(ref workspace) = cargo_toml.workspace {
-            if let Some(ref workspace_deps) = workspace.dependencies {
-                deps.extend(workspace_deps);
+
+        if let Some(lockfile) = lockfile.as_ref().filter(|_| registry.transitive) {
+            for package in lockfile.transitive(root) {
+                if names.contains(&package.name) {
+                    continue;
+                }
+                deps_flatten.push(dependency(
+                    "rust",
+                    &package.name,
+                    &package.resolved(),
+                    "",
+                    "transitive",
+                ));
             }
         }
 
         // Match dependencies and create flattened dependency list
-        let techs = match_dependencies(
-            &deps.keys().map(|s| s.to_string()).collect::<Vec<_>>(),
-            "rust",
-        );
+        let techs = match_dependencies(&names, "rust", registry);
 
-        let deps_flatten: Vec<Vec<String>> = deps
-            .into_iter()
-            .map(|(name, value)| {
-                let version = match value {
-                    Dependency::Simple(ref version) => version.to_string(),
-                    Dependency::Detailed {
-                        ref version,
-                        ref path,
-                        ref git,
-                        ref branch,
-                        ref rev,
-                        ..
-                    } => {
-                        if let Some(path_str) = path {
-                            let version_suffix =
-                                version.as_ref().map(|v| format!(":{}", v)).unwrap_or_default();
-                            format!("path:{}{}", path_str, version_suffix)
-                        } else if let Some(git_str) = git {
-                            let suffix = branch
-                                .as_ref()
-                                .or(rev.as_ref())
-                                .map(|s| s.as_str())
-                                .unwrap_or("latest");
-                            format!("git:{}#{}", git_str, suffix)
-                        } else {
-                            version
-                                .as_ref()
-                                .map(|s| s.to_string())
-                                .unwrap_or_else(|| "latest".to_string())
-                        }
-                    }
-                };
-                vec!["rust".to_string(), name.to_string(), version]
-            })
-            .collect();
-
-        pl.add_techs(&techs);
+        pl.add_techs(&techs, registry);
         pl.dependencies = deps_flatten;
 
         return Ok(pl);
@@ -141,10 +241,49 @@ pub fn detect_rust_component(
     Err(false)
 }
 
+/// Dependencies of a kind, followed by the ones specific to a platform
+fn dependencies(
+    cargo_toml: &CargoToml,
+    kind: impl Fn(&Tables) -> &Option<Dependencies>,
+) -> Vec<(String, Dependency)> {
+    let targets = cargo_toml.target.iter().flat_map(|targets| targets.values());
+
+    std::iter::once(&cargo_toml.tables)
+        .chain(targets)
+        .filter_map(|tables| kind(tables).clone())
+        .flatten()
+        .collect()
+}
+
+fn crate_dependency(
+    name: &str,
+    value: &DetailedDependency,
+    kind: Option<&str>,
+    root: Option<&str>,
+    lockfile: Option<&Lockfile>,
+) -> Vec<String> {
+    // Local crates are linked by their path, the version is only a requirement
+    let resolved = match &value.path {
+        Some(path) => format!("path:{}", path),
+        None => lockfile
+            .and_then(|lockfile| lockfile.resolve(root, name))
+            .map(|package| package.resolved())
+            .unwrap_or_default(),
+    };
+    let scope = kind.unwrap_or(if value.optional { "optional" } else { "" });
+
+    let mut dep = dependency("rust", name, &resolved, &value.declared(), scope);
+    if !value.features.is_empty() {
+        dep.push(format!("features:{}", value.features.join(",")));
+    }
+    dep
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::provider::fake::FakeProvider;
+    use std::collections::HashMap;
 
     #[test]
     fn test_detect_rust_component() {
@@ -186,48 +325,195 @@ workspace_dep = "1.0"
         }];
 
         // Test the function
-        let result = detect_rust_component(&files, &provider).unwrap();
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let result = detect_rust_component(&files, &provider, &registry).unwrap();
 
         // Verify results
         assert_eq!(result.name, "test_project");
         assert!(result.path.contains("Cargo.toml"));
 
         // Verify dependencies are correctly parsed
-        let deps = result.dependencies;
-        assert!(deps.contains(&vec![
-            "rust".to_string(),
-            "serde".to_string(),
-            "1.0".to_string()
-        ]));
-        assert!(deps.contains(&vec![
-            "rust".to_string(),
-            "tokio".to_string(),
-            "1.0".to_string()
-        ]));
-        assert!(deps.contains(&vec![
-            "rust".to_string(),
-            "axum".to_string(),
-            "git:https://github.com/tokio-rs/axum#main".to_string()
-        ]));
-        assert!(deps.contains(&vec![
-            "rust".to_string(),
-            "local_dep".to_string(),
-            "path:../local_dep".to_string()
-        ]));
-        assert!(deps.contains(&vec![
-            "rust".to_string(),
-            "mockall".to_string(),
-            "0.11".to_string()
-        ]));
-        assert!(deps.contains(&vec![
-            "rust".to_string(),
-            "cc".to_string(),
-            "1.0".to_string()
-        ]));
-        assert!(deps.contains(&vec![
-            "rust".to_string(),
-            "workspace_dep".to_string(),
-            "1.0".to_string()
-        ]));
+        assert_eq!(
+            result.dependencies,
+            vec![
+                dependency(
+                    "rust",
+                    "axum",
+                    "",
+                    "git:https://github.com/tokio-rs/axum#main",
+                    ""
+                ),
+                dependency(
+                    "rust",
+                    "local_dep",
+                    "path:../local_dep",
+                    "path:../local_dep",
+                    ""
+                ),
+                dependency("rust", "serde", "", "1.0", ""),
+                [
+                    dependency("rust", "tokio", "", "1.0", ""),
+                    vec!["features:full".to_string()]
+                ]
+                .concat(),
+                dependency("rust", "mockall", "", "0.11", "dev"),
+                dependency("rust", "cc", "", "1.0", "build"),
+                dependency("rust", "workspace_dep", "", "1.0", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_rust_component_lockfile() {
+        let cargo_content = r#"
+[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+serde = "1.0"
+shared = { path = "../shared" }
+
+[dev-dependencies]
+serde = { version = "1.0", features = ["derive"] }
+tokio = "1"
+"#;
+        let lock_content = r#"
+version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [
+ "serde",
+ "shared",
+ "tokio",
+]
+
+[[package]]
+name = "serde"
+version = "1.0.195"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "shared"
+version = "0.1.0"
+dependencies = [
+ "serde",
+]
+
+[[package]]
+name = "tokio"
+version = "1.35.1"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = [
+ "pin-project-lite",
+]
+
+[[package]]
+name = "pin-project-lite"
+version = "0.2.13"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+        let provider = FakeProvider::new(
+            HashMap::from_iter([("/crates/app/".to_string(), vec!["Cargo.toml".to_string()])]),
+            HashMap::from_iter([
+                (
+                    "/crates/app/Cargo.toml".to_string(),
+                    cargo_content.to_string(),
+                ),
+                ("/Cargo.lock".to_string(), lock_content.to_string()),
+            ]),
+        );
+        let files = provider.list_dir("/crates/app/");
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let result = detect_rust_component(&files, &provider, &registry).unwrap();
+
+        // The lockfile belongs to the workspace
+        assert_eq!(result.path.len(), 1);
+        assert_eq!(
+            result.dependencies,
+            vec![
+                dependency("rust", "serde", "1.0.195", "1.0", ""),
+                dependency("rust", "shared", "path:../shared", "path:../shared", ""),
+                [
+                    dependency("rust", "serde", "1.0.195", "1.0", "dev"),
+                    vec!["features:derive".to_string()],
+                ]
+                .concat(),
+                dependency("rust", "tokio", "1.35.1", "1", "dev"),
+            ]
+        );
+
+        let registry = RuleRegistry::with_defaults().unwrap().with_transitive(true);
+        let result = detect_rust_component(&files, &provider, &registry).unwrap();
+        assert_eq!(
+            result.dependencies[4..],
+            vec![dependency(
+                "rust",
+                "pin-project-lite",
+                "0.2.13",
+                "",
+                "transitive"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_detect_rust_component_workspace_member() {
+        let workspace = r#"
+[workspace]
+members = ["crates/*"]
+
+[workspace.dependencies]
+tokio = { version = "1.35", features = ["rt"] }
+shared = { path = "crates/shared", version = "0.1" }
+"#;
+        let member = r#"
+[package]
+name = "api"
+
+[dependencies]
+tokio = { workspace = true, features = ["rt", "macros"] }
+shared.workspace = true
+pg = { package = "tokio-postgres", version = "0.7" }
+
+[target.'cfg(unix)'.dependencies]
+nix = { version = "0.27", optional = true }
+
+[target.'cfg(windows)'.dev-dependencies]
+winapi = "0.3"
+"#;
+
+        let provider = FakeProvider::new(
+            HashMap::from_iter([("/crates/api/".to_string(), vec!["Cargo.toml".to_string()])]),
+            HashMap::from_iter([
+                ("/Cargo.toml".to_string(), workspace.to_string()),
+                ("/crates/api/Cargo.toml".to_string(), member.to_string()),
+            ]),
+        );
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let result =
+            detect_rust_component(&provider.list_dir("/crates/api/"), &provider, &registry)
+                .unwrap();
+
+        assert_eq!(result.name, "api");
+        assert_eq!(
+            result.dependencies,
+            vec![
+                dependency("rust", "tokio-postgres", "", "0.7", ""),
+                dependency("rust", "shared", "path:../shared", "0.1", ""),
+                [
+                    dependency("rust", "tokio", "", "1.35", ""),
+                    vec!["features:rt,macros".to_string()],
+                ]
+                .concat(),
+                dependency("rust", "nix", "", "0.27", "optional"),
+                dependency("rust", "winapi", "", "0.3", "dev"),
+            ]
+        );
     }
 }
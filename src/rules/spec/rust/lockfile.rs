@@ -0,0 +1,237 @@
+use serde::Deserialize;
+use std::collections::{BTreeSet, VecDeque};
+use std::path::Path;
+
+use crate::provider::base::BaseProvider;
+
+pub const LOCKFILE: &str = "Cargo.lock";
+
+/// Crates resolved by Cargo, for a single crate or the whole workspace.
+#[derive(Debug, Default, Deserialize)]
+pub struct Lockfile {
+    #[serde(rename = "package", default)]
+    packages: Vec<LockPackage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LockPackage {
+    pub name: String,
+    pub version: String,
+    /// None for workspace members and path dependencies
+    pub source: Option<String>,
+    /// `name`, `name version` or `name version (source)` when ambiguous
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+impl LockPackage {
+    /// Version with its source when it is not a registry, e.g: `git:https://github.com/a/b#<sha>`
+    pub fn resolved(&self) -> String {
+        match self.source.as_deref().and_then(|s| s.strip_prefix("git+")) {
+            Some(git) => {
+                let (url, commit) = git.split_once('#').unwrap_or((git, "latest"));
+                let url = url.split('?').next().unwrap_or(url);
+                format!("git:{}#{}", url, commit)
+            }
+            None => self.version.clone(),
+        }
+    }
+}
+
+impl Lockfile {
+    /// Package resolved for the dependency `name` of the crate `root`.
+    ///
+    /// Without `root` (virtual manifest), only a name locked once can be resolved.
+    pub fn resolve(&self, root: Option<&str>, name: &str) -> Option<&LockPackage> {
+        if let Some(root) = root.and_then(|root| self.member(root)) {
+            return root
+                .dependencies
+                .iter()
+                .find(|spec| spec.split(' ').next() == Some(name))
+                .and_then(|spec| self.find(spec));
+        }
+
+        let mut candidates = self.packages.iter().filter(|p| p.name == name);
+        match (candidates.next(), candidates.next()) {
+            (Some(package), None) => Some(package),
+            _ => None,
+        }
+    }
+
+    /// Every crate `root` depends on, directly or not, without workspace members.
+    ///
+    /// Without `root` (virtual manifest), every crate of the workspace.
+    pub fn transitive(&self, root: Option<&str>) -> Vec<&LockPackage> {
+        let Some(root) = root.and_then(|root| self.member(root)) else {
+            return self.packages.iter().filter(|p| p.source.is_some()).collect();
+        };
+
+        let mut seen: BTreeSet<(&str, &str, Option<&str>)> = BTreeSet::new();
+        let mut result = Vec::new();
+        let mut queue = VecDeque::from([root]);
+        while let Some(package) = queue.pop_front() {
+            for dep in package.dependencies.iter().filter_map(|spec| self.find(spec)) {
+                let key = (
+                    dep.name.as_str(),
+                    dep.version.as_str(),
+                    dep.source.as_deref(),
+                );
+                if !seen.insert(key) {
+                    continue;
+                }
+                if dep.source.is_some() {
+                    result.push(dep);
+                }
+                queue.push_back(dep);
+            }
+        }
+        result
+    }
+
+    fn member(&self, name: &str) -> Option<&LockPackage> {
+        self.packages.iter().find(|p| p.name == name && p.source.is_none())
+    }
+
+    /// Package for an entry of `dependencies`
+    fn find(&self, spec: &str) -> Option<&LockPackage> {
+        let mut parts = spec.splitn(3, ' ');
+        let name = parts.next()?;
+        let version = parts.next();
+        let source = parts.next().map(|s| s.trim_start_matches('(').trim_end_matches(')'));
+
+        self.packages.iter().find(|p| {
+            p.name == name
+                && version.is_none_or(|v| p.version == v)
+                && source.is_none_or(|s| p.source.as_deref() == Some(s))
+        })
+    }
+}
+
+/// Cargo.lock next to the manifest, or the one of its workspace in a parent folder.
+pub fn find_lockfile(manifest: &str, provider: &dyn BaseProvider) -> Option<(String, Lockfile)> {
+    let base = provider.base_path();
+    for dir in Path::new(manifest).ancestors().skip(1) {
+        let fp = dir.join(LOCKFILE).to_string_lossy().to_string();
+        if let Some(content) = provider.open(&fp) {
+            return match toml::from_str(&content) {
+                Ok(lockfile) => Some((fp, lockfile)),
+                Err(e) => {
+                    log::warn!("Failed to parse {}: {} - {}", LOCKFILE, fp, e);
+                    None
+                }
+            };
+        }
+        if dir == Path::new(&base) || !dir.starts_with(&base) {
+            break;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::fake::FakeProvider;
+    use std::collections::HashMap;
+
+    const CONTENT: &str = r#"
+version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [
+ "axum",
+ "rand 0.8.5",
+ "shared",
+]
+
+[[package]]
+name = "axum"
+version = "0.7.4"
+source = "git+https://github.com/tokio-rs/axum?branch=main#0123abc"
+
+[[package]]
+name = "rand"
+version = "0.7.3"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "rand"
+version = "0.8.5"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = [
+ "rand_core",
+]
+
+[[package]]
+name = "rand_core"
+version = "0.6.4"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "shared"
+version = "0.1.0"
+dependencies = [
+ "rand 0.7.3",
+]
+"#;
+
+    #[test]
+    fn test_resolve() {
+        let lockfile: Lockfile = toml::from_str(CONTENT).unwrap();
+
+        let rand = lockfile.resolve(Some("app"), "rand").unwrap();
+        assert_eq!(rand.resolved(), "0.8.5");
+        let axum = lockfile.resolve(Some("app"), "axum").unwrap();
+        assert_eq!(
+            axum.resolved(),
+            "git:https://github.com/tokio-rs/axum#0123abc"
+        );
+        assert!(lockfile.resolve(Some("app"), "rand_core").is_none());
+
+        // Ambiguous without the crate depending on it
+        assert!(lockfile.resolve(None, "rand").is_none());
+        assert_eq!(
+            lockfile.resolve(None, "rand_core").unwrap().version,
+            "0.6.4"
+        );
+    }
+
+    #[test]
+    fn test_transitive() {
+        let lockfile: Lockfile = toml::from_str(CONTENT).unwrap();
+
+        let crates: Vec<(&str, &str)> = lockfile
+            .transitive(Some("app"))
+            .into_iter()
+            .map(|p| (p.name.as_str(), p.version.as_str()))
+            .collect();
+        assert_eq!(
+            crates,
+            vec![
+                ("axum", "0.7.4"),
+                ("rand", "0.8.5"),
+                ("rand_core", "0.6.4"),
+                ("rand", "0.7.3"),
+            ]
+        );
+        assert_eq!(lockfile.transitive(None).len(), 4);
+    }
+
+    #[test]
+    fn test_find_lockfile_in_workspace() {
+        let provider = FakeProvider::new(
+            HashMap::new(),
+            HashMap::from_iter([("/Cargo.lock".to_string(), CONTENT.to_string())]),
+        );
+
+        let (fp, lockfile) = find_lockfile("/crates/app/Cargo.toml", &provider).unwrap();
+        assert_eq!(fp, "/Cargo.lock");
+        assert_eq!(lockfile.packages.len(), 6);
+
+        let provider = FakeProvider::new(HashMap::new(), HashMap::new());
+        assert!(find_lockfile("/crates/app/Cargo.toml", &provider).is_none());
+    }
+}
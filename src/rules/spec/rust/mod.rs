@@ -1,40 +1,46 @@
 pub mod component;
+pub mod lockfile;
+pub mod workspace;
 use component::detect_rust_component;
 
 use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
 use crate::types::rule::{Rule, RuleDependency, RuleFiles};
 
-pub fn register_rust() -> Result<(), String> {
-    register(Rule {
-        tech: String::from("rust"),
-        name: String::from("Rust"),
-        r#type: String::from("language"),
-        files: Some(RuleFiles::FilesArray {
-            files: vec![String::from("Cargo.toml")],
-        }),
-        dependencies: Some(vec![
-            RuleDependency {
-                r#type: String::from("docker"),
-                name: Some(String::from("rust")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("docker"),
-                name: Some(String::from("cimg/rust")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("docker"),
-                name: Some(String::from("circleci/rust")),
-                ..Default::default()
-            },
-            RuleDependency {
-                r#type: String::from("docker"),
-                name: Some(String::from("rustlang/rust")),
-                ..Default::default()
-            },
-        ]),
-        detect: Some(vec![detect_rust_component]),
-        ..Default::default()
-    })
+pub fn register_rust(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("rust"),
+            name: String::from("Rust"),
+            r#type: String::from("language"),
+            files: Some(RuleFiles::FilesArray {
+                files: vec![String::from("Cargo.toml")],
+            }),
+            dependencies: Some(vec![
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("rust")),
+                    example: Some(String::from("rust:1.74-slim")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("cimg/rust")),
+                    example: Some(String::from("cimg/rust:1.74")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("circleci/rust")),
+                    example: Some(String::from("circleci/rust:1.50")),
+                },
+                RuleDependency {
+                    r#type: String::from("docker"),
+                    name: Some(String::from("rustlang/rust")),
+                    example: Some(String::from("rustlang/rust:nightly")),
+                },
+            ]),
+            detect: Some(vec![detect_rust_component]),
+            ..Default::default()
+        },
+    )
 }
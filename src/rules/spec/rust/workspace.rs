@@ -0,0 +1,90 @@
+use std::path::{Path, PathBuf};
+
+use super::component::{CargoToml, Workspace};
+use crate::provider::base::{matches_glob, normalize_path, BaseProvider};
+
+/// Workspace a manifest belongs to, with the folder of its root.
+///
+/// Like Cargo, the first manifest with a `[workspace]` in a parent folder is the root,
+/// and it only applies if the crate is one of its members.
+pub(super) fn find_workspace(
+    manifest: &str,
+    cargo_toml: &CargoToml,
+    provider: &dyn BaseProvider,
+) -> Option<(PathBuf, Workspace)> {
+    let folder = normalize_path(Path::new(manifest).parent()?);
+    if let Some(workspace) = &cargo_toml.workspace {
+        return Some((folder, workspace.clone()));
+    }
+
+    let base = provider.base_path();
+    for dir in folder.ancestors().skip(1) {
+        if !dir.starts_with(&base) {
+            break;
+        }
+
+        let fp = dir.join("Cargo.toml").to_string_lossy().to_string();
+        let Some(content) = provider.open(&fp) else {
+            continue;
+        };
+        let parent: CargoToml = match toml::from_str(&content) {
+            Ok(parent) => parent,
+            Err(e) => {
+                log::warn!("Failed to parse Cargo.toml: {} - {}", fp, e);
+                return None;
+            }
+        };
+
+        let workspace = parent.workspace?;
+        let member = folder.strip_prefix(dir).ok()?;
+        return is_member(&workspace, member).then(|| (dir.to_path_buf(), workspace));
+    }
+
+    None
+}
+
+/// Whether a folder, relative to the workspace root, is matched by `members` and not excluded
+fn is_member(workspace: &Workspace, folder: &Path) -> bool {
+    let excluded = workspace
+        .exclude
+        .iter()
+        .any(|exclude| folder.starts_with(normalize_path(Path::new(exclude))));
+
+    !excluded && workspace.members.iter().any(|glob| matches_glob(glob, folder))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::fake::FakeProvider;
+    use std::collections::HashMap;
+
+    const ROOT: &str = r#"
+[workspace]
+members = ["crates/*", "tools/cli"]
+exclude = ["crates/legacy"]
+"#;
+
+    #[test]
+    fn test_find_workspace() {
+        let provider = FakeProvider::new(
+            HashMap::new(),
+            HashMap::from_iter([("/Cargo.toml".to_string(), ROOT.to_string())]),
+        );
+        let member: CargoToml = toml::from_str("[package]\nname = \"api\"").unwrap();
+
+        let (root, workspace) =
+            find_workspace("/crates/api/Cargo.toml", &member, &provider).unwrap();
+        assert_eq!(root, PathBuf::from("/"));
+        assert_eq!(workspace.members.len(), 2);
+
+        assert!(find_workspace("/tools/cli/Cargo.toml", &member, &provider).is_some());
+        assert!(find_workspace("/crates/legacy/Cargo.toml", &member, &provider).is_none());
+        assert!(find_workspace("/tools/other/Cargo.toml", &member, &provider).is_none());
+
+        // The root of the workspace is its own workspace
+        let root: CargoToml = toml::from_str(ROOT).unwrap();
+        let (folder, _) = find_workspace("/Cargo.toml", &root, &provider).unwrap();
+        assert_eq!(folder, PathBuf::from("/"));
+    }
+}
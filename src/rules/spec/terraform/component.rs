@@ -0,0 +1,546 @@
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    payload::payload::{dependency, Payload},
+    provider::base::{folder_name, BaseProvider, ProviderFile},
+    rules::{match_dependencies::match_dependencies, registry::RuleRegistry},
+};
+
+const LOCKFILE: &str = ".terraform.lock.hcl";
+const DEFAULT_REGISTRY: &str = "registry.terraform.io";
+
+lazy_static::lazy_static! {
+    static ref BLOCK_REG: Regex = Regex::new(
+        r#"^(resource|data|module|provider|terraform)\s*(?:"([^"]+)")?\s*(?:"([^"]+)")?\s*\{"#
+    )
+    .unwrap();
+    static ref ATTR_REG: Regex = Regex::new(r#"([\w-]+)\s*=\s*"([^"]*)""#).unwrap();
+    static ref ENTRY_REG: Regex = Regex::new(r"^([\w-]+)\s*=\s*\{").unwrap();
+}
+
+#[derive(Debug, Default)]
+struct TerraformFiles {
+    /// Exact versions from the lockfile, by provider address
+    locked: BTreeMap<String, String>,
+    /// Constraints from `required_providers`, by provider address
+    required: BTreeMap<String, String>,
+    resources: BTreeSet<String>,
+    modules: Vec<Module>,
+}
+
+#[derive(Debug, Default)]
+struct Module {
+    label: String,
+    source: Option<String>,
+    version: Option<String>,
+}
+
+/// Top-level block being read.
+enum Current {
+    Provider(String),
+    Module,
+    Terraform {
+        required_providers: bool,
+        /// Provider of `required_providers` being read
+        entry: Option<String>,
+    },
+    Other,
+}
+
+pub fn detect_terraform_component(
+    files: &Vec<ProviderFile>,
+    provider: &dyn BaseProvider,
+    registry: &RuleRegistry,
+) -> Result<Payload, bool> {
+    let mut parsed = TerraformFiles::default();
+    let mut paths: Vec<String> = Vec::new();
+
+    for file in files {
+        if file.name != LOCKFILE && !file.name.ends_with(".tf") {
+            continue;
+        }
+
+        let content = match provider.open(&file.fp) {
+            Some(content) => content,
+            None => continue,
+        };
+
+        parsed.read(&content);
+        paths.push(file.fp.clone());
+    }
+
+    if paths.is_empty() {
+        return Err(false);
+    }
+
+    // The infrastructure is a component of its own, named after its folder: the clouds and
+    // hostings it declares are not the ones of the package next to it
+    let mut infra = Payload::new(&folder_name(&paths[0], provider), &paths[0]);
+    infra.tech = Some("terraform".to_string());
+    infra.path.extend(paths.iter().cloned());
+
+    let providers: BTreeSet<&String> = parsed.locked.keys().chain(parsed.required.keys()).collect();
+
+    let mut deps: BTreeSet<Vec<String>> = BTreeSet::new();
+    let mut names: Vec<String> = Vec::new();
+    for address in providers {
+        let resolved = parsed.locked.get(address).map(String::as_str).unwrap_or_default();
+        let declared = parsed.required.get(address).map(String::as_str).unwrap_or("latest");
+        names.push(address.clone());
+        deps.insert(dependency("terraform", address, resolved, declared, ""));
+    }
+
+    for module in parsed.modules {
+        let Some(source) = module.source else {
+            continue;
+        };
+        if source.starts_with("./") || source.starts_with("../") {
+            let path = format!("path:{}", source);
+            deps.insert(dependency("terraform", &module.label, &path, &path, ""));
+            continue;
+        }
+        names.push(source.clone());
+        let version = module.version.as_deref().unwrap_or("latest");
+        deps.insert(dependency("terraform", &source, "", version, ""));
+    }
+
+    let resources: Vec<String> = parsed.resources.into_iter().collect();
+    for resource in &resources {
+        deps.insert(dependency("terraform.resource", resource, "", "", ""));
+    }
+
+    infra.add_techs(&match_dependencies(&names, "terraform", registry), registry);
+    infra.add_techs(
+        &match_dependencies(&resources, "terraform.resource", registry),
+        registry,
+    );
+    infra.dependencies = deps.into_iter().collect();
+
+    let mut pl = Payload::new("virtual", &paths[0]);
+    pl.add_child(infra);
+
+    Ok(pl)
+}
+
+impl TerraformFiles {
+    /// Read a .tf file or a lockfile, both are HCL.
+    fn read(&mut self, content: &str) {
+        let mut current = Current::Other;
+
+        for (depth, line) in hcl_lines(content) {
+            if depth == 0 {
+                current = self.open_block(&line);
+                continue;
+            }
+
+            match current {
+                Current::Provider(ref address) if depth == 1 => {
+                    if let Some(version) = attribute(&line, "version") {
+                        self.locked.insert(address.clone(), version);
+                    }
+                }
+                Current::Module if depth == 1 => {
+                    if let Some(module) = self.modules.last_mut() {
+                        module.source = attribute(&line, "source").or(module.source.take());
+                        module.version = attribute(&line, "version").or(module.version.take());
+                    }
+                }
+                Current::Terraform {
+                    ref mut required_providers,
+                    ref mut entry,
+                } => {
+                    if depth == 1 {
+                        *required_providers = line.starts_with("required_providers");
+                    } else if *required_providers {
+                        self.read_required_provider(depth, &line, entry);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn open_block(&mut self, line: &str) -> Current {
+        let Some(caps) = BLOCK_REG.captures(line) else {
+            return Current::Other;
+        };
+        let label = caps.get(2).map(|m| m.as_str().to_string());
+
+        // Data sources only read what exists elsewhere, they are not part of the infrastructure
+        match (&caps[1], label) {
+            ("resource", Some(resource)) => {
+                self.resources.insert(resource);
+                Current::Other
+            }
+            ("module", Some(label)) => {
+                self.modules.push(Module {
+                    label,
+                    ..Default::default()
+                });
+                Current::Module
+            }
+            // Only the lockfile has versions in provider blocks
+            ("provider", Some(address)) if address.contains('/') => Current::Provider(address),
+            ("terraform", _) => Current::Terraform {
+                required_providers: false,
+                entry: None,
+            },
+            _ => Current::Other,
+        }
+    }
+
+    /// Entries of `required_providers`, e.g:
+    /// `aws = { source = "hashicorp/aws", version = "~> 4.0" }`
+    fn read_required_provider(&mut self, depth: usize, line: &str, entry: &mut Option<String>) {
+        if depth == 2 {
+            let name = ENTRY_REG.captures(line).map(|caps| caps[1].to_string());
+            // Before Terraform 0.13: `aws = "~> 2.0"`
+            let legacy = ATTR_REG.captures(line).filter(|_| name.is_none());
+            let Some(name) = name.or(legacy.as_ref().map(|caps| caps[1].to_string())) else {
+                return;
+            };
+
+            let source = attribute(line, "source").unwrap_or_else(|| format!("hashicorp/{}", name));
+            let version = legacy
+                .map(|caps| caps[2].to_string())
+                .or_else(|| attribute(line, "version"))
+                .unwrap_or_else(|| "latest".to_string());
+            let address = provider_address(&source);
+            self.required.insert(address.clone(), version);
+            *entry = Some(address);
+            return;
+        }
+
+        // Attributes of a multi-line entry
+        let Some(address) = entry.clone() else {
+            return;
+        };
+        if let Some(source) = attribute(line, "source") {
+            let version = self.required.remove(&address).unwrap_or_default();
+            let address = provider_address(&source);
+            self.required.insert(address.clone(), version);
+            *entry = Some(address);
+        } else if let Some(version) = attribute(line, "version") {
+            self.required.insert(address, version);
+        }
+    }
+}
+
+/// Full address of a provider, e.g: `hashicorp/aws` is `registry.terraform.io/hashicorp/aws`
+fn provider_address(source: &str) -> String {
+    let source = source.to_lowercase();
+    if source.split('/').count() == 3 {
+        source
+    } else {
+        format!("{}/{}", DEFAULT_REGISTRY, source)
+    }
+}
+
+fn attribute(line: &str, name: &str) -> Option<String> {
+    ATTR_REG
+        .captures_iter(line)
+        .find(|caps| &caps[1] == name)
+        .map(|caps| caps[2].to_string())
+}
+
+/// Lines of HCL without comments and heredocs, with the depth of braces they start at.
+fn hcl_lines(content: &str) -> Vec<(usize, String)> {
+    let mut result = Vec::new();
+    let mut depth: usize = 0;
+    let mut in_comment = false;
+    let mut heredoc: Option<String> = None;
+
+    for raw in content.lines() {
+        if let Some(ref end) = heredoc {
+            if raw.trim() == end {
+                heredoc = None;
+            }
+            continue;
+        }
+
+        let start_depth = depth;
+        let mut line = String::new();
+        let mut chars = raw.chars().peekable();
+        let mut in_string = false;
+        let mut interpolation = 0;
+
+        while let Some(c) = chars.next() {
+            if in_comment {
+                if c == '*' && chars.peek() == Some(&'/') {
+                    chars.next();
+                    in_comment = false;
+                }
+                continue;
+            }
+
+            if in_string {
+                line.push(c);
+                match c {
+                    '\\' => line.extend(chars.next()),
+                    '$' if chars.peek() == Some(&'{') => {
+                        line.extend(chars.next());
+                        interpolation += 1;
+                    }
+                    '}' if interpolation > 0 => interpolation -= 1,
+                    '"' if interpolation == 0 => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+
+            match c {
+                '"' => {
+                    in_string = true;
+                    line.push(c);
+                }
+                '#' => break,
+                '/' if chars.peek() == Some(&'/') => break,
+                '/' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    in_comment = true;
+                }
+                '{' => {
+                    depth += 1;
+                    line.push(c);
+                }
+                '}' => {
+                    depth = depth.saturating_sub(1);
+                    line.push(c);
+                }
+                '<' if chars.peek() == Some(&'<') => {
+                    let rest: String = chars.by_ref().skip(1).collect();
+                    let marker: String = rest
+                        .trim_start_matches('-')
+                        .chars()
+                        .take_while(|c| c.is_alphanumeric() || *c == '_')
+                        .collect();
+                    if !marker.is_empty() {
+                        heredoc = Some(marker);
+                    }
+                    line.push_str("\"\"");
+                }
+                _ => line.push(c),
+            }
+        }
+
+        result.push((start_depth, line.trim().to_string()));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{fake::FakeProvider, fs::FsProvider};
+    use std::collections::HashMap;
+
+    fn dep(r#type: &str, name: &str, resolved: &str, declared: &str) -> Vec<String> {
+        dependency(r#type, name, resolved, declared, "")
+    }
+
+    #[test]
+    fn test_detect_terraform_component() {
+        let main_tf = r#"
+terraform {
+  required_providers {
+    aws = {
+      source  = "hashicorp/aws"
+      version = "~> 4.0"
+    }
+    google = { source = "hashicorp/google", version = ">= 4.0" }
+  }
+}
+
+provider "aws" {
+  region = "eu-west-1" # Ireland
+}
+
+resource "aws_db_instance" "main" {
+  engine = "postgres"
+  tags = {
+    Name = "${var.env}-db"
+  }
+  /* } */
+}
+
+data "aws_s3_bucket" "assets" {
+  bucket = "assets"
+}
+
+resource "aws_iam_policy" "policy" {
+  policy = <<EOF
+{
+  "Version": "2012-10-17"
+}
+EOF
+}
+
+module "vpc" {
+  source  = "terraform-aws-modules/vpc/aws"
+  version = "5.0.0"
+}
+
+module "db" {
+  source = "./modules/db"
+}
+"#;
+        let lock = r#"
+provider "registry.terraform.io/hashicorp/aws" {
+  version     = "4.67.0"
+  constraints = "~> 4.0"
+}
+"#;
+
+        let provider = FakeProvider::new(
+            HashMap::from_iter([(
+                "/infra".to_string(),
+                vec![".terraform.lock.hcl".to_string(), "main.tf".to_string()],
+            )]),
+            HashMap::from_iter([
+                ("/infra/main.tf".to_string(), main_tf.to_string()),
+                ("/infra/.terraform.lock.hcl".to_string(), lock.to_string()),
+            ]),
+        );
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let result =
+            detect_terraform_component(&provider.list_dir("/infra"), &provider, &registry).unwrap();
+
+        // Nothing is added to the component of the folder
+        assert_eq!(result.name, "virtual");
+        assert!(result.techs.is_empty());
+        assert!(result.dependencies.is_empty());
+        assert_eq!(result.childs.len(), 1);
+
+        let infra = &result.childs[0];
+        assert_eq!(infra.name, "infra");
+        assert_eq!(infra.tech.as_deref(), Some("terraform"));
+        assert_eq!(infra.path.len(), 2);
+        // The data source is not a resource of this infrastructure
+        assert_eq!(
+            infra.dependencies,
+            vec![
+                dep("terraform", "db", "path:./modules/db", "path:./modules/db"),
+                dep(
+                    "terraform",
+                    "registry.terraform.io/hashicorp/aws",
+                    "4.67.0",
+                    "~> 4.0"
+                ),
+                dep(
+                    "terraform",
+                    "registry.terraform.io/hashicorp/google",
+                    "",
+                    ">= 4.0"
+                ),
+                dep("terraform", "terraform-aws-modules/vpc/aws", "", "5.0.0"),
+                dep("terraform.resource", "aws_db_instance", "", ""),
+                dep("terraform.resource", "aws_iam_policy", "", ""),
+            ]
+        );
+
+        // Cloud resources become components
+        let mut childs: Vec<&str> = infra.childs.iter().filter_map(|c| c.tech.as_deref()).collect();
+        childs.sort();
+        assert_eq!(childs, vec!["aws", "aws.rds", "gcp"]);
+    }
+
+    #[test]
+    fn test_detect_terraform_fixture() {
+        let provider = FsProvider::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/__fixtures__/terraform"
+        ))
+        .unwrap();
+        let registry = RuleRegistry::with_defaults().unwrap();
+
+        let files = provider.list_dir(&provider.base_path());
+        let result = detect_terraform_component(&files, &provider, &registry).unwrap();
+        assert!(result.in_component.is_none());
+
+        let infra = &result.childs[0];
+        assert_eq!(infra.name, "terraform");
+        assert_eq!(
+            infra.dependencies,
+            vec![
+                dep(
+                    "terraform",
+                    "registry.terraform.io/hashicorp/google",
+                    "4.61.0",
+                    "latest"
+                ),
+                dep(
+                    "terraform",
+                    "registry.terraform.io/vercel/vercel",
+                    "4.61.0",
+                    "latest"
+                ),
+            ]
+        );
+        assert!(infra.techs.contains("gcp"));
+        assert!(infra.techs.contains("vercel"));
+    }
+
+    #[test]
+    fn test_detect_terraform_next_to_package() {
+        let main_tf = r#"
+terraform {
+  required_providers {
+    vercel = { source = "vercel/vercel" }
+  }
+}
+"#;
+        let provider = FakeProvider::new(
+            HashMap::from_iter([(
+                "/".to_string(),
+                vec!["main.tf".to_string(), "package.json".to_string()],
+            )]),
+            HashMap::from_iter([
+                ("/main.tf".to_string(), main_tf.to_string()),
+                (
+                    "/package.json".to_string(),
+                    r#"{ "name": "app" }"#.to_string(),
+                ),
+            ]),
+        );
+
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let mut root = Payload::new("main", "/");
+        root.recurse(&provider, "/", &registry);
+
+        // The package is not hosted by what the infrastructure declares
+        let app = root.childs.iter().find(|c| c.name == "app").unwrap();
+        assert!(app.in_component.is_none());
+        assert!(!app.techs.contains("vercel"));
+        let infra = root.childs.iter().find(|c| c.tech.as_deref() == Some("terraform")).unwrap();
+        assert!(infra.techs.contains("vercel"));
+    }
+
+    #[test]
+    fn test_detect_terraform_legacy_required_providers() {
+        let mut parsed = TerraformFiles::default();
+        parsed.read("terraform {\n  required_providers {\n    aws = \"~> 2.0\"\n  }\n}\n");
+
+        assert_eq!(
+            parsed.required,
+            BTreeMap::from([(
+                "registry.terraform.io/hashicorp/aws".to_string(),
+                "~> 2.0".to_string()
+            )])
+        );
+    }
+
+    #[test]
+    fn test_detect_terraform_nothing() {
+        let provider = FakeProvider::new(
+            HashMap::from_iter([("/".to_string(), vec!["variables.tfvars".to_string()])]),
+            HashMap::new(),
+        );
+
+        let res =
+            detect_terraform_component(&provider.list_dir("/"), &provider, &RuleRegistry::new());
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,26 @@
+pub mod component;
+use component::detect_terraform_component;
+
+use crate::rules::register::register;
+use crate::rules::registry::RuleRegistry;
+use crate::types::rule::{Rule, RuleFiles};
+
+pub fn register_terraform(registry: &mut RuleRegistry) -> Result<(), String> {
+    register(
+        registry,
+        Rule {
+            tech: String::from("terraform"),
+            name: String::from("Terraform"),
+            r#type: String::from("tool"),
+            files: Some(RuleFiles::FilesArray {
+                files: vec![
+                    String::from(".terraform.lock.hcl"),
+                    String::from(".terraform/"),
+                ],
+            }),
+            extensions: Some(vec![String::from("tf")]),
+            detect: Some(vec![detect_terraform_component]),
+            ..Default::default()
+        },
+    )
+}
@@ -0,0 +1,381 @@
+use std::fmt;
+
+use crate::rules::loader::dependency_pattern;
+use crate::rules::registry::{RuleRegistry, DEPENDENCY_TYPES};
+use crate::types::rule::{Rule, RuleDependency, RuleFiles};
+use crate::types::techs::{ALLOWED_KEYS, TECH_TYPES};
+
+/// Why a rule can't be registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    /// `tech` is not in `ALLOWED_KEYS`, nor a custom key of the registry
+    UnknownKey { tech: String },
+    /// `type` is not in `TECH_TYPES`
+    UnknownType { tech: String, r#type: String },
+    /// A dependency `type` no detector reports, it would never match
+    UnknownDependencyType { tech: String, r#type: String },
+    InvalidRegex {
+        tech: String,
+        pattern: String,
+        error: String,
+    },
+    /// A matcher that would match nothing, or everything: `name: ""`, `files: []`
+    EmptyMatcher { tech: String, matcher: String },
+    /// The same dependency already points to another tech, or twice to this one
+    DuplicateDependency {
+        tech: String,
+        r#type: String,
+        name: String,
+        other: String,
+    },
+    /// An `example` its own matcher does not match
+    ExampleMismatch {
+        tech: String,
+        example: String,
+        pattern: String,
+    },
+    /// A matcher without an `example` to test it with
+    MissingExample { tech: String, pattern: String },
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::UnknownKey { tech } => write!(f, "{}: unknown tech key", tech),
+            RuleError::UnknownType { tech, r#type } => {
+                write!(f, "{}: unknown type \"{}\"", tech, r#type)
+            }
+            RuleError::UnknownDependencyType { tech, r#type } => {
+                write!(f, "{}: unknown dependency type \"{}\"", tech, r#type)
+            }
+            RuleError::InvalidRegex {
+                tech,
+                pattern,
+                error,
+            } => write!(f, "{}: invalid regex \"{}\": {}", tech, pattern, error),
+            RuleError::EmptyMatcher { tech, matcher } => write!(f, "{}: empty {}", tech, matcher),
+            RuleError::DuplicateDependency {
+                tech,
+                r#type,
+                name,
+                other,
+            } => write!(
+                f,
+                "{}: dependency {} \"{}\" is already used by {}",
+                tech, r#type, name, other
+            ),
+            RuleError::ExampleMismatch {
+                tech,
+                example,
+                pattern,
+            } => write!(
+                f,
+                "{}: example \"{}\" does not match \"{}\"",
+                tech, example, pattern
+            ),
+            RuleError::MissingExample { tech, pattern } => {
+                write!(f, "{}: no example to test \"{}\" with", tech, pattern)
+            }
+        }
+    }
+}
+
+/// Check a rule before registering it, against the rules already in the registry.
+pub fn validate_rule(rule: &Rule, registry: &RuleRegistry) -> Result<(), Vec<RuleError>> {
+    let tech = || rule.tech.clone();
+    let mut errors = Vec::new();
+
+    if !ALLOWED_KEYS.contains(&rule.tech.as_str()) && !registry.custom_keys.contains(&rule.tech) {
+        errors.push(RuleError::UnknownKey { tech: tech() });
+    }
+    if !TECH_TYPES.contains(&rule.r#type.as_str()) {
+        errors.push(RuleError::UnknownType {
+            tech: tech(),
+            r#type: rule.r#type.clone(),
+        });
+    }
+
+    let deps = rule.dependencies.as_deref().unwrap_or_default();
+    for (i, dep) in deps.iter().enumerate() {
+        errors.extend(validate_dependency(rule, dep, &deps[..i], registry));
+    }
+
+    match &rule.files {
+        Some(RuleFiles::FilesArray { files }) => {
+            if files.is_empty() || files.iter().any(|file| file.is_empty()) {
+                errors.push(RuleError::EmptyMatcher {
+                    tech: tech(),
+                    matcher: "files".to_string(),
+                });
+            }
+            if let Some(example) = rule.example.as_ref().filter(|_| !files.is_empty()) {
+                if !files.contains(example) {
+                    errors.push(RuleError::ExampleMismatch {
+                        tech: tech(),
+                        example: example.clone(),
+                        pattern: files.join(", "),
+                    });
+                }
+            }
+        }
+        Some(RuleFiles::FilesRegex { files, example })
+        | Some(RuleFiles::MatchFullPath { files, example, .. }) => {
+            if files.as_str().is_empty() {
+                errors.push(RuleError::EmptyMatcher {
+                    tech: tech(),
+                    matcher: "files".to_string(),
+                });
+            }
+            if !example.is_empty() && !files.is_match(example) {
+                errors.push(RuleError::ExampleMismatch {
+                    tech: tech(),
+                    example: example.clone(),
+                    pattern: files.to_string(),
+                });
+            }
+        }
+        Some(RuleFiles::NoFiles) | None => {}
+    }
+
+    if let Some(extensions) = &rule.extensions {
+        if extensions.is_empty() || extensions.iter().any(|ext| ext.is_empty()) {
+            errors.push(RuleError::EmptyMatcher {
+                tech: tech(),
+                matcher: "extensions".to_string(),
+            });
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn validate_dependency(
+    rule: &Rule,
+    dep: &RuleDependency,
+    previous: &[RuleDependency],
+    registry: &RuleRegistry,
+) -> Vec<RuleError> {
+    let tech = || rule.tech.clone();
+    let mut errors = Vec::new();
+
+    if !DEPENDENCY_TYPES.contains(&dep.r#type.as_str()) {
+        errors.push(RuleError::UnknownDependencyType {
+            tech: tech(),
+            r#type: dep.r#type.clone(),
+        });
+    }
+
+    let Some(name) = &dep.name else {
+        return errors;
+    };
+    if name.is_empty() {
+        errors.push(RuleError::EmptyMatcher {
+            tech: tech(),
+            matcher: format!("{} dependency name", dep.r#type),
+        });
+        return errors;
+    }
+
+    let pattern = match dependency_pattern(name) {
+        Ok(pattern) => pattern,
+        Err(e) => {
+            errors.push(RuleError::InvalidRegex {
+                tech: tech(),
+                pattern: name.clone(),
+                error: e.to_string(),
+            });
+            return errors;
+        }
+    };
+
+    let same = |other: &RuleDependency| other.r#type == dep.r#type && other.name == dep.name;
+    let other = if previous.iter().any(same) {
+        Some(rule.tech.clone())
+    } else {
+        registry
+            .registered_rules
+            .iter()
+            .find(|other| other.dependencies.iter().flatten().any(same))
+            .map(|other| other.tech.clone())
+    };
+    if let Some(other) = other {
+        errors.push(RuleError::DuplicateDependency {
+            tech: tech(),
+            r#type: dep.r#type.clone(),
+            name: name.clone(),
+            other,
+        });
+    }
+
+    if let Some(example) = &dep.example {
+        if !example_names(&dep.r#type, example).iter().any(|e| pattern.is_match(e)) {
+            errors.push(RuleError::ExampleMismatch {
+                tech: tech(),
+                example: example.clone(),
+                pattern: name.clone(),
+            });
+        }
+    }
+
+    errors
+}
+
+/// Names an example can be matched as, an image is reported without its tag (`node:18`)
+pub(crate) fn example_names(r#type: &str, example: &str) -> Vec<String> {
+    let mut names = vec![example.to_string()];
+    if r#type == "docker" {
+        let name_start = example.rfind('/').map_or(0, |idx| idx + 1);
+        if let Some(idx) = example[name_start..].find(':') {
+            names.push(example[..name_start + idx].to_string());
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    fn rule(tech: &str, r#type: &str) -> Rule {
+        Rule {
+            tech: tech.to_string(),
+            name: tech.to_string(),
+            r#type: r#type.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn dep(r#type: &str, name: &str, example: Option<&str>) -> RuleDependency {
+        RuleDependency {
+            r#type: r#type.to_string(),
+            name: Some(name.to_string()),
+            example: example.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_validate_rule() {
+        let registry = RuleRegistry::new();
+        let mut valid = rule("postgresql", "db");
+        valid.dependencies = Some(vec![
+            dep("npm", "pg", Some("pg")),
+            dep("docker", "postgres", Some("postgres:15.1-alpine")),
+            dep(
+                "deno",
+                "/x/postgres@/",
+                Some("https://deno.land/x/postgres@v0.17.0/mod.ts"),
+            ),
+        ]);
+        valid.files = Some(RuleFiles::FilesRegex {
+            files: Regex::new(r"^postgres\.conf$").unwrap(),
+            example: "postgres.conf".to_string(),
+        });
+        assert_eq!(validate_rule(&valid, &registry), Ok(()));
+
+        let errors = validate_rule(&rule("postgress", "database"), &registry).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                RuleError::UnknownKey {
+                    tech: "postgress".to_string()
+                },
+                RuleError::UnknownType {
+                    tech: "postgress".to_string(),
+                    r#type: "database".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            errors[1].to_string(),
+            "postgress: unknown type \"database\""
+        );
+    }
+
+    #[test]
+    fn test_validate_rule_matchers() {
+        let registry = RuleRegistry::new();
+        let mut invalid = rule("redis", "db");
+        invalid.dependencies = Some(vec![
+            dep("npm", "", None),
+            dep("npm", "/(redis/", None),
+            dep("npm", "ioredis", Some("redis")),
+            dep("cargo", "redis", None),
+        ]);
+        invalid.files = Some(RuleFiles::FilesArray { files: vec![] });
+        invalid.extensions = Some(vec!["".to_string()]);
+
+        let errors: Vec<String> = validate_rule(&invalid, &registry)
+            .unwrap_err()
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                "redis: empty npm dependency name",
+                "redis: invalid regex \"/(redis/\": regex parse error:\n    (redis\n    ^\nerror: unclosed group",
+                "redis: example \"redis\" does not match \"ioredis\"",
+                "redis: unknown dependency type \"cargo\"",
+                "redis: empty files",
+                "redis: empty extensions",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_rule_duplicates() {
+        let mut first = rule("postgresql", "db");
+        first.dependencies = Some(vec![dep("npm", "pg", None)]);
+        let registry = RuleRegistry::from_rules(vec![first]).unwrap();
+
+        let mut second = rule("supabase", "db");
+        second.dependencies = Some(vec![
+            dep("npm", "pg", None),
+            dep("docker", "pg", None),
+            dep("docker", "pg", None),
+        ]);
+        let errors = validate_rule(&second, &registry).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                RuleError::DuplicateDependency {
+                    tech: "supabase".to_string(),
+                    r#type: "npm".to_string(),
+                    name: "pg".to_string(),
+                    other: "postgresql".to_string(),
+                },
+                RuleError::DuplicateDependency {
+                    tech: "supabase".to_string(),
+                    r#type: "docker".to_string(),
+                    name: "pg".to_string(),
+                    other: "supabase".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_builtin_rules() {
+        let registry = RuleRegistry::with_defaults().unwrap();
+        let mut check = RuleRegistry::new();
+        for rule in &registry.registered_rules {
+            assert_eq!(validate_rule(rule, &check), Ok(()));
+            check.registered_rules.push(rule.clone());
+        }
+    }
+
+    #[test]
+    fn test_example_names() {
+        assert_eq!(example_names("docker", "node:18"), vec!["node:18", "node"]);
+        assert_eq!(
+            example_names("docker", "ghcr.io:443/acme/api:1.0"),
+            vec!["ghcr.io:443/acme/api:1.0", "ghcr.io:443/acme/api"]
+        );
+        assert_eq!(example_names("npm", "pg"), vec!["pg"]);
+    }
+}
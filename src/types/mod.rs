@@ -6,8 +6,8 @@ use crate::payload::payload::Payload;
 #[derive(Clone)]
 pub struct GraphEdge {
     pub target: Payload,
-    read: bool,
-    write: bool,
+    pub read: bool,
+    pub write: bool,
 }
 
 pub type Dependency = (String, String, String);
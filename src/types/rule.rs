@@ -1,34 +1,58 @@
 use regex::Regex;
+use serde::Deserialize;
 use std::collections::HashSet;
 
 use crate::{
     payload::payload::Payload,
     provider::base::{BaseProvider, ProviderFile},
+    rules::registry::RuleRegistry,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct RuleDependency {
     pub r#type: String,
+    /// Exact name, glob with `*`, or `/regex/` (see `DependencyKind`)
     pub name: Option<String>,
+    /// A dependency it matches, e.g: `postgres:16` for `postgres`, required by `self_test`
     pub example: Option<String>,
 }
 
-impl Default for RuleDependency {
-    fn default() -> Self {
-        RuleDependency {
-            r#type: String::new(),
-            name: None,
-            example: None,
+/// How the name of a `RuleDependency` is compared to a dependency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyKind {
+    /// Compared as is, dots and all, e.g: `@opentelemetry/instrumentation-pg`
+    Exact,
+    /// `*` matches anything, the rest as is, e.g: `@aws-sdk/*`
+    Glob,
+    /// Written `/regex/`, searched anywhere in the dependency, e.g: `/x/postgres@/` for URLs
+    Regex,
+}
+
+impl DependencyKind {
+    pub fn of(name: &str) -> Self {
+        if name.len() > 2 && name.starts_with('/') && name.ends_with('/') {
+            DependencyKind::Regex
+        } else if name.contains('*') {
+            DependencyKind::Glob
+        } else {
+            DependencyKind::Exact
         }
     }
 }
 
-#[derive(Clone, Debug)]
+/// A tech and how to find it.
+///
+/// Rules can also be written as data (YAML, JSON or TOML, see `loader::load_rules_from_str`),
+/// everything but `detect` which is code.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Rule {
     pub tech: String,
     pub name: String,
     pub r#type: String,
     pub dependencies: Option<Vec<RuleDependency>>,
+    #[serde(skip)]
     pub detect: Option<Vec<ComponentMatcher>>,
     pub extensions: Option<Vec<String>>,
     pub files: Option<RuleFiles>,
@@ -50,7 +74,12 @@ impl Default for Rule {
     }
 }
 
-#[derive(Clone, Debug)]
+/// In a data file, a list of names or `{ regex, example, match_full_path }`.
+///
+/// A name ending with `/` only matches a folder, e.g: `.vercel/`. A full path is relative to
+/// the root of the analysis, e.g: `.github/workflows/ci.yml`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(try_from = "RawRuleFiles")]
 pub enum RuleFiles {
     FilesRegex {
         files: Regex,
@@ -67,8 +96,54 @@ pub enum RuleFiles {
     },
 }
 
-pub type ComponentMatcher =
-    fn(files: &Vec<ProviderFile>, provider: &dyn BaseProvider) -> Result<Payload, bool>;
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawRuleFiles {
+    Names(Vec<String>),
+    Regex(RawRegexFiles),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRegexFiles {
+    regex: String,
+    example: Option<String>,
+    #[serde(default)]
+    match_full_path: bool,
+}
+
+impl TryFrom<RawRuleFiles> for RuleFiles {
+    type Error = String;
+
+    fn try_from(raw: RawRuleFiles) -> Result<Self, Self::Error> {
+        let (regex, example, match_full_path) = match raw {
+            RawRuleFiles::Names(files) => return Ok(RuleFiles::FilesArray { files }),
+            RawRuleFiles::Regex(raw) => (
+                raw.regex,
+                raw.example.unwrap_or_default(),
+                raw.match_full_path,
+            ),
+        };
+
+        let files = Regex::new(&regex).map_err(|e| format!("invalid files regex: {}", e))?;
+        Ok(if match_full_path {
+            RuleFiles::MatchFullPath {
+                match_full_path,
+                files,
+                example,
+            }
+        } else {
+            RuleFiles::FilesRegex { files, example }
+        })
+    }
+}
+
+pub type ComponentMatcher = fn(
+    files: &Vec<ProviderFile>,
+    provider: &dyn BaseProvider,
+    registry: &RuleRegistry,
+) -> Result<Payload, bool>;
 
-pub type TechMatcher = Box<dyn Fn(Vec<ProviderFile>) -> Result<(Rule, String), bool> + Send>;
-pub type ExtensionMatcher = Box<dyn Fn(HashSet<String>) -> Result<(Rule, String), bool> + Send>;
+pub type TechMatcher = Box<dyn Fn(Vec<ProviderFile>) -> Result<(Rule, String), bool> + Send + Sync>;
+pub type ExtensionMatcher =
+    Box<dyn Fn(HashSet<String>) -> Result<(Rule, String), bool> + Send + Sync>;